@@ -1,11 +1,17 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use colored::Colorize;
-use reqwest;
 use semver::Version;
-use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
 use surreal_type_generator::{
     step_1_parse_sql, step_2_interpret,
-    step_3_codegen::{self},
+    step_3_codegen::{
+        self,
+        typescript::{ClientTemplate, GeometryOutput},
+    },
     utils::printing::indent,
 };
 
@@ -33,6 +39,65 @@ struct Cli {
         default_value = "import { type RecordId, Surreal } from 'surrealdb'"
     )]
     header: String,
+
+    /// The built-in client to generate at the bottom of the output file
+    #[clap(long, value_enum, default_value_t = Client::Class)]
+    client: Client,
+
+    /// How `geometry` values are represented in the generated TypeScript
+    /// `classes` uses the SDK's `Geometry*` classes, which you must import in the header
+    #[clap(long, value_enum, default_value_t = Geometry::Geojson)]
+    geometry: Geometry,
+
+    /// A custom client template file, overrides `--client`
+    /// Supports `{{#each queries}}...{{/each}}` blocks with `{{name}}`, `{{fn_name}}`, `{{query}}`, `{{result}}` and `{{variables}}` placeholders
+    #[clap(long)]
+    template: Option<String>,
+
+    /// Optionally generate a fixtures file with factory functions for each query result and table
+    /// eg: `./fixtures.ts`
+    #[clap(long)]
+    fixtures: Option<String>,
+
+    /// Header to add to the top of the fixtures file
+    /// If you specify this, you must import in RecordId, Duration and Decimal classes
+    #[clap(
+        long,
+        default_value = "import { RecordId, Duration, Decimal } from 'surrealdb'"
+    )]
+    fixtures_header: String,
+
+    /// Optionally generate Go structs and query functions for the surrealdb.go client
+    /// eg: `./queries/queries.go`
+    #[clap(long)]
+    go: Option<String>,
+
+    /// The package name of the generated Go file
+    #[clap(long, default_value = "queries")]
+    go_package: String,
+
+    /// Optionally generate a GraphQL SDL schema from the tables, views and queries
+    /// eg: `./schema.graphql`
+    #[clap(long)]
+    graphql: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Client {
+    /// A `TypedSurreal` class extending `Surreal`
+    Class,
+    /// A `TypedSurreal` class wrapping an existing `Surreal` instance
+    Wrapper,
+    /// A function per query taking a `Surreal` instance
+    Functions,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Geometry {
+    /// Plain GeoJSON objects, eg: `{ type: "Point", coordinates: [number, number] }`
+    Geojson,
+    /// The SDK's `GeometryPoint`, `GeometryLine`, etc. classes
+    Classes,
 }
 
 fn fetch_latest_version() -> Option<Version> {
@@ -57,7 +122,7 @@ pub fn main() {
             eprintln!(
                 "{}\n{}",
                 " ✕ Error: ".on_bright_red().bright_white().bold(),
-                err.to_string()
+                err
             );
 
             println!(
@@ -105,12 +170,9 @@ fn check_latest_version() {
             println!();
         } else {
             println!(
-                "{}",
-                format!(
-                    "{} You're using the latest version of surreal-codegen: {}",
-                    "✓".bright_green().bold(),
-                    CURRENT_VERSION.bright_green()
-                )
+                "{} You're using the latest version of surreal-codegen: {}",
+                "✓".bright_green().bold(),
+                CURRENT_VERSION.bright_green()
             );
         }
     } else {
@@ -178,7 +240,35 @@ pub fn interpret() -> anyhow::Result<()> {
         "Generating typescript output".white()
     );
 
-    let output = step_3_codegen::typescript::generate_typescript_output(&types, &cli.header)?;
+    let client = match &cli.template {
+        Some(template) => {
+            ClientTemplate::Custom(step_3_codegen::read_file(&PathBuf::from(template))?)
+        }
+        None => match cli.client {
+            Client::Class => ClientTemplate::Class,
+            Client::Wrapper => ClientTemplate::Wrapper,
+            Client::Functions => ClientTemplate::Functions,
+        },
+    };
+
+    let geometry = match cli.geometry {
+        Geometry::Geojson => GeometryOutput::GeoJson,
+        Geometry::Classes => GeometryOutput::Classes,
+    };
+
+    let mut output = step_3_codegen::typescript::generate_typescript_output(
+        &types,
+        &cli.header,
+        &client,
+        geometry,
+    )?;
+
+    // the fixtures file imports the select shape of every table from the output file
+    if cli.fixtures.is_some() {
+        output.push_str(
+            &step_3_codegen::typescript::generate_typescript_record_types(&state, geometry)?,
+        );
+    }
 
     colored::control::unset_override();
 
@@ -190,5 +280,75 @@ pub fn interpret() -> anyhow::Result<()> {
         cli.output.bright_green()
     );
 
+    if let Some(fixtures) = &cli.fixtures {
+        let fixtures_output = step_3_codegen::typescript::generate_typescript_fixtures(
+            &types,
+            &state,
+            &cli.fixtures_header,
+            &relative_import_path(Path::new(fixtures), Path::new(&cli.output)),
+            geometry,
+        )?;
+
+        std::fs::write(fixtures, fixtures_output)?;
+        println!(
+            "{} {} '{}'",
+            "➜".bright_green().bold(),
+            "Wrote fixtures to".white(),
+            fixtures.bright_green()
+        );
+    }
+
+    if let Some(go) = &cli.go {
+        let go_output = step_3_codegen::go::generate_go_output(&types, &cli.go_package)?;
+
+        std::fs::write(go, go_output)?;
+        println!(
+            "{} {} '{}'",
+            "➜".bright_green().bold(),
+            "Wrote Go output to".white(),
+            go.bright_green()
+        );
+    }
+
+    if let Some(graphql) = &cli.graphql {
+        let graphql_output =
+            step_3_codegen::graphql::generate_graphql_schema(&types, state.clone())?;
+
+        std::fs::write(graphql, graphql_output)?;
+        println!(
+            "{} {} '{}'",
+            "➜".bright_green().bold(),
+            "Wrote GraphQL schema to".white(),
+            graphql.bright_green()
+        );
+    }
+
     Ok(())
 }
+
+/// The path to import `to` from `from` in TypeScript, eg: `./types` or `../types`
+fn relative_import_path(from: &Path, to: &Path) -> String {
+    let normal_components = |path: &Path| {
+        path.components()
+            .filter(|component| !matches!(component, Component::CurDir))
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let from_dir = normal_components(from.parent().unwrap_or(Path::new("")));
+    let to = normal_components(&to.with_extension(""));
+
+    let common = from_dir
+        .iter()
+        .zip(to.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts = vec![".."; from_dir.len() - common];
+    if parts.is_empty() {
+        parts.push(".");
+    }
+    parts.extend(to[common..].iter().map(String::as_str));
+
+    parts.join("/")
+}
@@ -1,42 +1,232 @@
 use std::collections::BTreeMap;
 
-use surrealdb::sql::{parse, Cast, Param, Statement, Value};
+use surrealdb::sql::{parse, Block, Cast, Entry, Kind, Literal, Param, Statement, Value};
 
 pub struct QueryParsed {
     pub statements: Vec<Statement>,
     pub casted_parameters: BTreeMap<String, crate::Kind>,
+    /// Names given to statements with a `-- @name foo` comment, keyed by the index in `statements`
+    pub statement_names: BTreeMap<usize, String>,
 }
 
 pub fn parse_query(query: &str) -> Result<QueryParsed, anyhow::Error> {
     // collect and filter out all the variable castings
     let mut parameter_types = BTreeMap::new();
     let mut statements = Vec::new();
+    // whether each statement in the query is a cast, used to resolve `-- @name` comments
+    let mut casts = Vec::new();
 
     for stmt in parse(query)?.into_iter() {
+        casts.push(is_parameter_cast(&stmt));
         match stmt {
             Statement::Value(Value::Cast(box Cast {
                 0: kind,
                 1: Value::Param(Param { 0: ident, .. }),
                 ..
             })) => {
-                parameter_types.insert(ident.to_string(), kind);
+                parameter_types.insert(ident.0, expand_discriminated_objects(kind));
             }
             _ => statements.push(stmt),
         }
     }
 
+    let statement_names = parse_statement_names(query, &casts)?;
+
     Ok(QueryParsed {
         statements,
         casted_parameters: parameter_types,
+        statement_names,
     })
 }
 
+/// Finds `-- @name foo` comments, and resolves which statement they are naming
+/// by counting the (non-cast) statements that come before the comment.
+///
+/// ```surql
+/// -- @name users
+/// SELECT * FROM user;
+/// -- @name total
+/// SELECT VALUE count() FROM ONLY user GROUP ALL;
+/// ```
+fn parse_statement_names(
+    query: &str,
+    casts: &[bool],
+) -> Result<BTreeMap<usize, String>, anyhow::Error> {
+    let StatementComments {
+        comments,
+        statement_count,
+    } = scan_statement_comments(query)?;
+
+    let mut names = BTreeMap::new();
+
+    for (position, name) in comments {
+        if name.is_empty()
+            || name.starts_with(|c: char| c.is_ascii_digit())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            anyhow::bail!("Invalid statement name `{}` in `-- @name` comment", name);
+        }
+
+        if statement_count != casts.len() {
+            anyhow::bail!(
+                "Could not match `-- @name {}` to a statement, as the query's statements couldn't be separated",
+                name
+            );
+        }
+
+        if position >= casts.len() {
+            anyhow::bail!("`-- @name {}` comment is not followed by a statement", name);
+        }
+
+        let index = casts[..position]
+            .iter()
+            .filter(|is_cast| !**is_cast)
+            .count();
+
+        if names.values().any(|existing| existing == name) {
+            anyhow::bail!("Duplicate statement name `{}`", name);
+        }
+
+        if let Some(existing) = names.insert(index, name.to_string()) {
+            anyhow::bail!(
+                "Statement was named twice, as `{}` and `{}`",
+                existing,
+                name
+            );
+        }
+    }
+
+    Ok(names)
+}
+
+struct StatementComments<'a> {
+    /// The names of `-- @name` comments, with the number of statements before them
+    comments: Vec<(usize, &'a str)>,
+    statement_count: usize,
+}
+
+/// Scans the query for `-- @name` comments, skipping over strings, escaped identifiers and comments.
+fn scan_statement_comments(query: &str) -> Result<StatementComments<'_>, anyhow::Error> {
+    let mut comments = Vec::new();
+    let mut statement_count = 0;
+    // whether the current statement has started, eg: after `SELECT` but before the `;`
+    let mut in_statement = false;
+    // the nesting of brackets, as `;` only ends a statement outside of any blocks
+    let mut depth = 0usize;
+
+    let mut chars = query.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        match c {
+            // a comment only starts at the start of a line or after whitespace or a `;`, so `$x--1` isn't a comment
+            '-' if query[..start]
+                .chars()
+                .next_back()
+                .is_none_or(|previous| previous.is_whitespace() || previous == ';')
+                && chars.next_if(|(_, c)| *c == '-').is_some() =>
+            {
+                let end = query[start..]
+                    .find('\n')
+                    .map_or(query.len(), |end| start + end);
+                while chars.next_if(|(index, _)| *index < end).is_some() {}
+
+                if let Some(name) = get_statement_name(&query[start + 2..end]) {
+                    if in_statement || depth > 0 {
+                        anyhow::bail!(
+                            "`-- @name {}` comments must be placed between statements",
+                            name
+                        );
+                    }
+                    comments.push((statement_count, name));
+                }
+                continue;
+            }
+            '#' => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+                continue;
+            }
+            '/' if chars.next_if(|(_, c)| *c == '/').is_some() => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+                continue;
+            }
+            '/' if chars.next_if(|(_, c)| *c == '*').is_some() => {
+                let mut previous = ' ';
+                for (_, c) in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                continue;
+            }
+            '\'' | '"' | '`' | '⟨' => {
+                let end = match c {
+                    '⟨' => '⟩',
+                    c => c,
+                };
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        c if c == end => break,
+                        _ => {}
+                    }
+                }
+            }
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ';' if depth == 0 => {
+                if in_statement {
+                    statement_count += 1;
+                    in_statement = false;
+                }
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            _ => {}
+        }
+
+        in_statement = true;
+    }
+
+    if in_statement {
+        statement_count += 1;
+    }
+
+    Ok(StatementComments {
+        comments,
+        statement_count,
+    })
+}
+
+/// Returns the name from the text of a `--` comment, eg: `foo` for `-- @name foo`
+fn get_statement_name(comment: &str) -> Option<&str> {
+    let rest = comment.trim_start().strip_prefix("@name")?;
+
+    match rest.chars().next() {
+        None => Some(""),
+        Some(c) if c.is_whitespace() => Some(rest.trim()),
+        // eg: `-- @names`, which isn't a name comment
+        Some(_) => None,
+    }
+}
+
+fn is_parameter_cast(stmt: &Statement) -> bool {
+    matches!(
+        stmt,
+        Statement::Value(Value::Cast(box Cast {
+            1: Value::Param(_), ..
+        }))
+    )
+}
+
 /// In surreal 2.0, `RETURN` statements now can "early-exit" in the AST
 /// This effectively treats `BEGIN/COMMIT` grouped statements as a block
 /// but only if they contain a `RETURN` statement
 ///
-/// So we need to turn and represent the Vec<Statement> into a Vec<Vec<Statement>> representing
-/// every branching case and scenario.
+/// This converts the statements of such a transaction (without the `BEGIN` and `COMMIT`)
+/// into the equivalent block, so the transaction has a single result: the kind of the block.
 ///
 /// ### Example 1:
 /// ```surql
@@ -54,10 +244,7 @@ pub fn parse_query(query: &str) -> Result<QueryParsed, anyhow::Error> {
 /// 1: <number> (2)
 /// ```
 ///
-/// OR
-///
-/// /// What this effectively means, is that a transaction containing a RETURN statement
-/// is effectively converted into a block-like AST, looking like the following:
+/// As the transaction is converted into a block-like AST, looking like the following:
 ///
 /// ```sql
 /// {
@@ -70,7 +257,7 @@ pub fn parse_query(query: &str) -> Result<QueryParsed, anyhow::Error> {
 ///
 /// ### Example 2:
 ///
-/// And without a `RETURN`
+/// And without a `RETURN`, the transaction is not converted
 ///
 /// ```surql
 /// BEGIN;
@@ -94,7 +281,7 @@ pub fn parse_query(query: &str) -> Result<QueryParsed, anyhow::Error> {
 /// RETURN 2;
 /// COMMIT;
 /// ```
-/// Would turn into an AST looking like the following:
+/// Would turn into an AST looking like the following, with a single result of `1 | 2`:
 /// ```sql
 /// {
 ///     IF condition {
@@ -115,17 +302,79 @@ pub fn parse_query(query: &str) -> Result<QueryParsed, anyhow::Error> {
 /// COMMIT;
 /// RETURN 2;
 /// ```
-/// Would turn into an AST looking like the following:
-/// ```sql
-/// {
-///     IF condition {
-///         RETURN 1;
-///     }
-///     RETURN 2;
-/// }
-/// ```
-pub fn statements_to_block_ast(
-    statements: Vec<Statement>,
-) -> Result<Vec<Statement>, anyhow::Error> {
-    Ok(statements)
+/// Has no `RETURN` directly in the transaction, so it is not converted. The transaction either
+/// exits early with the result `[1]`, or falls through with the result of the `IF` statement `[NONE]`,
+/// which are unioned into the results `[1 | NONE, 2]` by the interpreter.
+pub fn statements_to_block_ast(statements: Vec<Statement>) -> Result<Block, anyhow::Error> {
+    let mut entries = Vec::new();
+
+    for stmt in statements {
+        entries.push(match stmt {
+            Statement::Value(value) => Entry::Value(value),
+            Statement::Set(set) => Entry::Set(set),
+            Statement::Ifelse(ifelse) => Entry::Ifelse(ifelse),
+            Statement::Select(select) => Entry::Select(select),
+            Statement::Create(create) => Entry::Create(create),
+            Statement::Update(update) => Entry::Update(update),
+            Statement::Upsert(upsert) => Entry::Upsert(upsert),
+            Statement::Delete(delete) => Entry::Delete(delete),
+            Statement::Relate(relate) => Entry::Relate(relate),
+            Statement::Insert(insert) => Entry::Insert(insert),
+            Statement::Output(output) => Entry::Output(output),
+            Statement::Define(define) => Entry::Define(define),
+            Statement::Remove(remove) => Entry::Remove(remove),
+            Statement::Rebuild(rebuild) => Entry::Rebuild(rebuild),
+            Statement::Alter(alter) => Entry::Alter(alter),
+            Statement::Throw(throw) => Entry::Throw(throw),
+            Statement::Break(break_statement) => Entry::Break(break_statement),
+            Statement::Continue(continue_statement) => Entry::Continue(continue_statement),
+            Statement::Foreach(foreach) => Entry::Foreach(foreach),
+            stmt => anyhow::bail!(
+                "Unsupported statement type in a transaction with a `RETURN`: `{}`",
+                stmt
+            ),
+        });
+    }
+
+    let mut block = Block::default();
+    block.0 = entries;
+
+    Ok(block)
+}
+
+/// SurrealDB parses a union of objects with a common literal field, eg: `{ kind: "a" } | { kind: "b" }`
+/// as a `Literal::DiscriminatedObject`, which is expanded back into a union of the objects.
+fn expand_discriminated_objects(kind: Kind) -> Kind {
+    match kind {
+        Kind::Literal(Literal::DiscriminatedObject(_, variants)) => Kind::Either(
+            variants
+                .into_iter()
+                .map(|fields| {
+                    Kind::Literal(Literal::Object(
+                        fields
+                            .into_iter()
+                            .map(|(key, kind)| (key, expand_discriminated_objects(kind)))
+                            .collect(),
+                    ))
+                })
+                .collect(),
+        ),
+        Kind::Literal(Literal::Object(fields)) => Kind::Literal(Literal::Object(
+            fields
+                .into_iter()
+                .map(|(key, kind)| (key, expand_discriminated_objects(kind)))
+                .collect(),
+        )),
+        Kind::Option(box kind) => Kind::Option(Box::new(expand_discriminated_objects(kind))),
+        Kind::Either(kinds) => Kind::Either(
+            kinds
+                .into_iter()
+                .map(expand_discriminated_objects)
+                .collect(),
+        ),
+        Kind::Array(box kind, size) => {
+            Kind::Array(Box::new(expand_discriminated_objects(kind)), size)
+        }
+        kind => kind,
+    }
 }
@@ -3,11 +3,11 @@ use std::collections::BTreeMap;
 use surrealdb::sql::{
     parse,
     statements::{
-        DefineFieldStatement, DefineFunctionStatement, DefineStatement, DefineTableStatement,
-        IfelseStatement, ThrowStatement,
+        DefineFieldStatement, DefineFunctionStatement, DefineIndexStatement, DefineStatement,
+        DefineTableStatement, IfelseStatement, ThrowStatement,
     },
-    Block, Entry, Expression, Fields, Function, Groups, Idiom, Kind, Param, Part, Query, Statement,
-    Tables, Value,
+    Block, Entry, Expression, Fields, Function, Groups, Idiom, Index, Kind, Param, Part, Query,
+    Statement, Tables, Value,
 };
 
 use crate::kind;
@@ -32,6 +32,8 @@ pub struct ViewParsed {
 pub struct FunctionParsed {
     pub name: String,
     pub arguments: Vec<(String, Kind)>,
+    /// The declared return type, eg: `string` for `DEFINE FUNCTION fn::foo() -> string { ... }`
+    pub returns: Option<Kind>,
     pub block: Block,
 }
 
@@ -48,6 +50,10 @@ pub struct TableParsed {
     pub name: String,
     pub id_value_type: Kind,
     pub fields: BTreeMap<String, FieldParsed>,
+    /// Whether the table is `SCHEMAFULL`, so fields which aren't defined can't be written
+    pub schemafull: bool,
+    /// The fields with a full-text `SEARCH` index, eg: `body` for `DEFINE INDEX ... FIELDS body SEARCH ANALYZER ...`
+    pub search_indexes: Vec<String>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -64,27 +70,41 @@ pub struct FieldParsed {
 
 impl FieldParsed {
     pub fn compute_create_type(&self) -> anyhow::Result<Kind> {
-        Ok(match &self.field_type {
-            FieldType::Simple => match self.is_optional || self.has_default {
-                true => Kind::Option(Box::new(self.return_type.clone())),
-                false => self.return_type.clone(),
-            },
-            FieldType::NestedObject(obj) => {
-                let mut fields = BTreeMap::new();
-                for (key, value) in obj {
-                    if !value.has_override_value {
-                        fields.insert(key.clone(), value.compute_create_type()?);
-                    }
-                }
+        self.compute_write_type(true)
+    }
 
-                let fields = kind!(Obj fields);
+    /// The type of the field when an existing record's content is set, eg: `UPDATE ... CONTENT $x`,
+    /// which is the same as when it is created, but without any `READONLY` fields, which keep their stored values.
+    pub fn compute_content_type(&self) -> anyhow::Result<Kind> {
+        self.compute_write_type(false)
+    }
 
-                match self.is_optional || self.has_default {
-                    true => Kind::Option(Box::new(fields)),
-                    false => fields,
+    fn compute_write_type(&self, include_readonly: bool) -> anyhow::Result<Kind> {
+        let write_fields = |obj: &BTreeMap<String, FieldParsed>| {
+            let mut fields = BTreeMap::new();
+            for (key, value) in obj {
+                if !value.has_override_value && (include_readonly || !value.readonly) {
+                    fields.insert(key.clone(), value.compute_write_type(include_readonly)?);
                 }
             }
-            _ => anyhow::bail!("TODO: Unsupported field type: {:?}", self.field_type),
+            Ok::<_, anyhow::Error>(kind!(Obj fields))
+        };
+
+        let kind = match &self.field_type {
+            FieldType::Simple => self.return_type.clone(),
+            FieldType::NestedObject(obj) => write_fields(obj)?,
+            FieldType::NestedArray(box FieldType::Simple) => kind!(Arr self.return_type.clone()),
+            FieldType::NestedArray(box FieldType::NestedObject(obj)) => {
+                kind!(Arr write_fields(obj)?)
+            }
+            FieldType::NestedArray(..) => {
+                anyhow::bail!("Nested array in nested array are not yet supported")
+            }
+        };
+
+        Ok(match self.is_optional || self.has_default {
+            true => Kind::Option(Box::new(kind)),
+            false => kind,
         })
     }
 
@@ -130,10 +150,35 @@ impl FieldParsed {
         })
     }
 
+    /// Returns the kind of the field in a `MERGE`, which is a deep partial of the field,
+    /// where `READONLY` fields and fields with a `VALUE` clause can't be written.
     pub fn compute_update_type(&self) -> anyhow::Result<Kind> {
-        anyhow::bail!("TODO: Query interpretation for UPDATE statements is not yet supported")
-        // return both flatten types
-        // ignore readonlys
+        Ok(kind!(Opt(match &self.field_type {
+            FieldType::Simple => self.return_type.clone(),
+            FieldType::NestedObject(obj) => {
+                let mut fields = BTreeMap::new();
+                for (key, value) in obj {
+                    if !value.readonly && !value.has_override_value {
+                        fields.insert(key.clone(), value.compute_update_type()?);
+                    }
+                }
+
+                kind!(Obj fields)
+            }
+            FieldType::NestedArray(box FieldType::Simple) => kind!(Arr self.return_type.clone()),
+            // arrays are replaced rather than merged, so their items must be complete
+            FieldType::NestedArray(box FieldType::NestedObject(obj)) => {
+                let mut fields = BTreeMap::new();
+                for (key, value) in obj {
+                    if !value.has_override_value {
+                        fields.insert(key.clone(), value.compute_create_type()?);
+                    }
+                }
+
+                kind!(Arr kind!(Obj fields))
+            }
+            _ => anyhow::bail!("TODO: Unsupported field type: {:?}", self.field_type),
+        })))
     }
 }
 
@@ -148,6 +193,19 @@ impl TableParsed {
         Ok(fields)
     }
 
+    /// The fields of a document which sets an existing record's content, eg: `UPDATE ... CONTENT $x`,
+    /// where `READONLY` fields keep their stored values, so are left out.
+    /// `UPDATE ... REPLACE $x` doesn't keep them, so it uses the create fields instead.
+    pub fn compute_content_fields(&self) -> anyhow::Result<BTreeMap<String, Kind>> {
+        let mut fields = BTreeMap::new();
+        for (key, field) in &self.fields {
+            if !field.readonly && !field.has_override_value {
+                fields.insert(key.clone(), field.compute_content_type()?);
+            }
+        }
+        Ok(fields)
+    }
+
     pub fn compute_select_fields(&self) -> anyhow::Result<BTreeMap<String, Kind>> {
         let mut fields = BTreeMap::new();
         for (key, value) in &self.fields {
@@ -159,7 +217,9 @@ impl TableParsed {
     pub fn compute_update_fields(&self) -> anyhow::Result<BTreeMap<String, Kind>> {
         let mut fields = BTreeMap::new();
         for (key, value) in &self.fields {
-            fields.insert(key.clone(), value.compute_update_type()?);
+            if !value.readonly && !value.has_override_value {
+                fields.insert(key.clone(), value.compute_update_type()?);
+            }
         }
         Ok(fields)
     }
@@ -168,6 +228,7 @@ impl TableParsed {
 fn parse_table(
     table: &DefineTableStatement,
     field_definitions: &Vec<(Idiom, DefineFieldStatement)>,
+    search_indexes: &[String],
 ) -> anyhow::Result<TableParsed> {
     // insert the implicit id field
     let mut fields = BTreeMap::from([(
@@ -193,12 +254,11 @@ fn parse_table(
         let mut to_insert = FieldParsed {
             name: match &idiom[idiom.len() - 1] {
                 Part::Field(ident) => ident.to_string(),
+                // the items of an array, eg: `tags.*`
+                Part::All => "*".into(),
                 _ => anyhow::bail!("Invalid path `{}`", idiom),
             },
-            is_optional: match return_type {
-                Kind::Option(..) => true,
-                _ => false,
-            },
+            is_optional: matches!(return_type, Kind::Option(..)),
             field_type: match &return_type {
                 Kind::Any => match field.flex {
                     false => FieldType::NestedObject(BTreeMap::new()),
@@ -234,7 +294,7 @@ fn parse_table(
             to_insert.has_default = true;
         }
 
-        insert_into_object(&idiom, &mut fields, to_insert)?;
+        insert_into_object(idiom, &mut fields, to_insert)?;
     }
 
     // Handle edge case where DEFINE FIELD id ON foo TYPE string is used
@@ -248,11 +308,13 @@ fn parse_table(
         }
     };
 
-    return Ok(TableParsed {
+    Ok(TableParsed {
         name: table.name.to_string(),
         id_value_type,
         fields,
-    });
+        schemafull: table.full,
+        search_indexes: search_indexes.to_vec(),
+    })
 }
 
 fn value_uses_value_param(value: &Value) -> Result<bool, anyhow::Error> {
@@ -267,7 +329,7 @@ fn value_uses_value_param(value: &Value) -> Result<bool, anyhow::Error> {
             false
         }
         Value::Object(object) => {
-            for (_, value) in object.iter() {
+            for value in object.values() {
                 if value_uses_value_param(value)? {
                     return Ok(true);
                 }
@@ -324,13 +386,10 @@ fn entry_uses_value_param(entry: &Entry) -> Result<bool, anyhow::Error> {
         Entry::Throw(ThrowStatement { error, .. }) => value_uses_value_param(error)?,
         Entry::Continue(_) | Entry::Break(_) => false,
         Entry::Ifelse(IfelseStatement { close, exprs, .. }) => {
-            match close {
-                Some(close) => {
-                    if value_uses_value_param(close)? {
-                        return Ok(true);
-                    }
+            if let Some(close) = close {
+                if value_uses_value_param(close)? {
+                    return Ok(true);
                 }
-                None => {}
             };
 
             for (v1, v2) in exprs.iter() {
@@ -371,6 +430,7 @@ pub fn parse_schema(schema: &str) -> Result<SchemaParsed, anyhow::Error> {
     struct TableInfo {
         definition: DefineTableStatement,
         fields: Vec<(Idiom, DefineFieldStatement)>,
+        search_indexes: Vec<String>,
     }
 
     let mut tables = BTreeMap::new();
@@ -402,6 +462,7 @@ pub fn parse_schema(schema: &str) -> Result<SchemaParsed, anyhow::Error> {
                             TableInfo {
                                 definition: table.clone(),
                                 fields: Vec::new(),
+                                search_indexes: Vec::new(),
                             },
                         );
                     }
@@ -420,10 +481,31 @@ pub fn parse_schema(schema: &str) -> Result<SchemaParsed, anyhow::Error> {
 
                 table.fields.push((field.name.clone(), field));
             }
+            Statement::Define(DefineStatement::Index(DefineIndexStatement {
+                what,
+                cols,
+                index: Index::Search(_),
+                ..
+            })) => {
+                let table = match tables.get_mut(what.as_str()) {
+                    Some(table) => table,
+                    None => {
+                        anyhow::bail!(
+                            "You tried to define an index on a table that hasn't been defined: `{}`",
+                            what
+                        );
+                    }
+                };
+
+                for col in cols.iter() {
+                    table.search_indexes.push(col.to_string());
+                }
+            }
             Statement::Define(DefineStatement::Function(DefineFunctionStatement {
                 name,
                 args,
                 block,
+                returns,
                 ..
             })) => {
                 functions.insert(
@@ -432,8 +514,9 @@ pub fn parse_schema(schema: &str) -> Result<SchemaParsed, anyhow::Error> {
                         name: name.to_string(),
                         arguments: args
                             .iter()
-                            .map(|(ident, kind)| Ok((ident.to_string(), kind.clone())))
+                            .map(|(ident, kind)| Ok((ident.0.clone(), kind.clone())))
                             .collect::<Result<Vec<(String, Kind)>, anyhow::Error>>()?,
+                        returns: returns.clone(),
                         block: block.clone(),
                     },
                 );
@@ -446,16 +529,19 @@ pub fn parse_schema(schema: &str) -> Result<SchemaParsed, anyhow::Error> {
     let tables = {
         let mut new_tables = BTreeMap::new();
         for (name, table) in tables.iter() {
-            new_tables.insert(name.clone(), parse_table(&table.definition, &table.fields)?);
+            new_tables.insert(
+                name.clone(),
+                parse_table(&table.definition, &table.fields, &table.search_indexes)?,
+            );
         }
         new_tables
     };
 
-    return Ok(SchemaParsed {
+    Ok(SchemaParsed {
         tables,
         functions,
         views,
-    });
+    })
 }
 
 fn insert_into_object(
@@ -474,6 +560,13 @@ fn insert_into_object(
     }
     let next_part = idiom.first().unwrap();
     match next_part {
+        // eg: `DEFINE FIELD tags.* ON user TYPE string`
+        Part::Field(field_ident) if idiom.len() == 2 && idiom[1] == Part::All => {
+            match fields.get_mut(field_ident.as_str()) {
+                Some(array) => set_array_item_type(array, field),
+                None => anyhow::bail!("Field `{}` is not a nested object or array", field_ident),
+            }
+        }
         Part::Field(field_ident) => match fields.get_mut(field_ident.as_str()) {
             Some(FieldParsed {
                 field_type: FieldType::NestedObject(fields),
@@ -496,6 +589,34 @@ fn insert_into_object(
     }
 }
 
+/// Sets the item type of an array field from an `array.*` field definition,
+/// where the `return_type` of a `NestedArray(Simple)` field is the type of its items.
+fn set_array_item_type(array: &mut FieldParsed, item: FieldParsed) -> anyhow::Result<()> {
+    let item_type = match item.is_optional {
+        true => Kind::Option(Box::new(item.return_type)),
+        false => item.return_type,
+    };
+
+    match (&array.field_type, &array.return_type, &item.field_type) {
+        // object items are defined by their nested fields instead, eg: `tags.*.name`
+        (FieldType::NestedArray(box FieldType::NestedObject(_)), _, FieldType::NestedObject(_)) => {
+        }
+        (FieldType::NestedArray(box FieldType::NestedObject(nested)), _, FieldType::Simple)
+            if nested.is_empty() =>
+        {
+            array.field_type = FieldType::NestedArray(Box::new(FieldType::Simple));
+            array.return_type = item_type;
+        }
+        (FieldType::Simple, Kind::Array(..) | Kind::Set(..), FieldType::Simple) => {
+            array.field_type = FieldType::NestedArray(Box::new(FieldType::Simple));
+            array.return_type = item_type;
+        }
+        _ => anyhow::bail!("Field `{}` is not an array of `{}`", array.name, item_type),
+    }
+
+    Ok(())
+}
+
 fn insert_into_array_type(
     idiom: &[Part],
     array_type: &mut FieldType,
@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+
+use surrealdb::sql::{Block, Entry};
+
+use crate::Kind;
+
+use super::{
+    return_types::get_value_return_type,
+    schema::QueryState,
+    statements::{
+        get_create_statement_return_type, get_delete_statement_return_type,
+        get_foreach_statement_flow, get_ifelse_statement_flow, get_insert_statement_return_type,
+        get_relate_statement_return_type, get_return_statement_return_type,
+        get_select_statement_return_type, get_update_statement_return_type,
+        get_upsert_statement_return_type, interpret_let_statement,
+    },
+    utils::union_kinds,
+};
+
+/// The result of interpreting a block, or a statement containing blocks such as `IF`,
+/// where `RETURN` statements exit every enclosing block until they reach a subquery,
+/// function call or top level statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockFlow {
+    /// The kind the block evaluates to when it runs to completion,
+    /// or `None` if it always exits early, eg: every branch of an `IF` contains a `RETURN` or `THROW`
+    pub value: Option<Kind>,
+    /// The kinds of the `RETURN` statements which exit the block early
+    pub returns: Vec<Kind>,
+    /// The narrowed kinds of parameters for the entries after the block,
+    /// eg: `$user` is not `NONE` after `IF $user = NONE { RETURN "not found"; }`
+    pub narrowed: BTreeMap<String, Kind>,
+}
+
+impl BlockFlow {
+    pub fn value(kind: Kind) -> Self {
+        BlockFlow {
+            value: Some(kind),
+            returns: Vec::new(),
+            narrowed: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the kind of the block where the `RETURN` statements are caught,
+    /// which is the union of the value it runs to completion with, and any `RETURN`s.
+    /// A block which otherwise returns and runs to completion with `NONE`, eg: an `IF` without an `ELSE`
+    /// whose branches all `RETURN`, is optional.
+    pub fn into_kind(self) -> Kind {
+        match self.value {
+            Some(Kind::Null) if !self.returns.is_empty() => union_kinds(
+                self.returns
+                    .into_iter()
+                    .map(|kind| Kind::Option(Box::new(kind))),
+            ),
+            value => union_kinds(value.into_iter().chain(self.returns)),
+        }
+    }
+}
+
+/// Interprets the entries of a block in its own stack frame, eg: `{ LET $x = 1; RETURN $x; }`
+pub fn get_block_flow(block: &Block, state: &mut QueryState) -> Result<BlockFlow, anyhow::Error> {
+    state.push_stack_frame();
+
+    let flow = get_entries_flow(&block.0, state);
+
+    state.pop_stack_frame();
+
+    flow
+}
+
+/// Interprets entries in the current stack frame, so any `LET` statements remain defined afterwards,
+/// eg: the statements of a transaction which contains a `RETURN`
+pub fn get_entries_flow(
+    entries: &[Entry],
+    state: &mut QueryState,
+) -> Result<BlockFlow, anyhow::Error> {
+    let mut value = Kind::Null;
+    let mut returns = Vec::new();
+
+    for entry in entries.iter() {
+        value = match entry {
+            Entry::Set(set) => interpret_let_statement(set, state)?,
+            Entry::Output(output) => {
+                returns.push(get_return_statement_return_type(output, state)?);
+
+                // any entries after a `RETURN` are never reached
+                return Ok(BlockFlow {
+                    value: None,
+                    returns,
+                    narrowed: BTreeMap::new(),
+                });
+            }
+            Entry::Ifelse(ifelse) => {
+                let flow = get_ifelse_statement_flow(ifelse, state)?;
+                returns.extend(flow.returns);
+
+                for (name, kind) in flow.narrowed {
+                    state.set_local(&name, kind);
+                }
+
+                match flow.value {
+                    Some(kind) => kind,
+                    // every branch returned or threw early
+                    None => {
+                        return Ok(BlockFlow {
+                            value: None,
+                            returns,
+                            narrowed: BTreeMap::new(),
+                        })
+                    }
+                }
+            }
+            Entry::Foreach(foreach) => {
+                let flow = get_foreach_statement_flow(foreach, state)?;
+                returns.extend(flow.returns);
+                Kind::Null
+            }
+            Entry::Throw(throw) => {
+                get_value_return_type(&throw.error, &BTreeMap::new(), state)?;
+
+                // `THROW` never evaluates to a value, so it is excluded from the block's kind
+                return Ok(BlockFlow {
+                    value: None,
+                    returns,
+                    narrowed: BTreeMap::new(),
+                });
+            }
+            Entry::Break(_) | Entry::Continue(_) => {
+                if !state.in_loop {
+                    anyhow::bail!("`{}` can only be used inside a `FOR` loop", entry);
+                }
+
+                // the rest of the loop's block is skipped
+                return Ok(BlockFlow {
+                    value: None,
+                    returns,
+                    narrowed: BTreeMap::new(),
+                });
+            }
+            Entry::Value(value) => get_value_return_type(value, &BTreeMap::new(), state)?,
+            Entry::Select(select) => get_select_statement_return_type(select, state)?,
+            Entry::Create(create) => get_create_statement_return_type(create, state)?,
+            Entry::Update(update) => get_update_statement_return_type(update, state)?,
+            Entry::Upsert(upsert) => get_upsert_statement_return_type(upsert, state)?,
+            Entry::Delete(delete) => get_delete_statement_return_type(delete, state)?,
+            Entry::Relate(relate) => get_relate_statement_return_type(relate, state)?,
+            Entry::Insert(insert) => get_insert_statement_return_type(insert, state)?,
+            _ => anyhow::bail!("Entry type: {} has not been implemented", entry),
+        };
+    }
+
+    Ok(BlockFlow {
+        value: Some(value),
+        returns,
+        narrowed: BTreeMap::new(),
+    })
+}
@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+
+use surrealdb::sql::{Cond, Expression, Operator, Part, Subquery, Value};
+
+use crate::{kind, Kind};
+
+use super::{
+    return_types::{get_expression_return_type, get_value_return_type},
+    schema::QueryState,
+    utils::{get_value_table, split_optional_kind},
+};
+
+/// Interprets the `WHERE` clause of a statement against each of the tables it reads from,
+/// validating full-text searches and inferring the kinds of parameters compared to fields,
+/// eg: `$email` is a `string` in `WHERE email = $email`.
+pub fn interpret_where_condition(
+    cond: &Cond,
+    what: &[Value],
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    for table in what.iter() {
+        let table_name = get_value_table(table, state)?;
+        let fields = state.table_select_fields(&table_name)?;
+        interpret_condition(&cond.0, &table_name, &fields, state)?;
+    }
+
+    Ok(())
+}
+
+fn interpret_condition(
+    value: &Value,
+    table_name: &str,
+    fields: &BTreeMap<String, Kind>,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    match value {
+        Value::Expression(box expr @ Expression::Binary { l, o, r }) => match o {
+            Operator::Matches(..) => {
+                if let Some(table) = state.schema.schema.tables.get(table_name) {
+                    let field = l.to_string();
+                    if !table.search_indexes.contains(&field) {
+                        anyhow::bail!(
+                            "Field `{}` on table `{}` must have a `SEARCH` index to be used with the `@@` operator",
+                            field,
+                            table_name
+                        );
+                    }
+                }
+                get_expression_return_type(expr, fields, state)?;
+            }
+            _ => {
+                infer_compared_parameter(l, o, r, fields, state);
+                interpret_condition(l, table_name, fields, state)?;
+                interpret_condition(r, table_name, fields, state)?;
+            }
+        },
+        Value::Expression(box Expression::Unary { v, .. }) => {
+            interpret_condition(v, table_name, fields, state)?;
+        }
+        Value::Subquery(box Subquery::Value(value)) => {
+            interpret_condition(value, table_name, fields, state)?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Infers the kind of a parameter without a known kind, from the field it is compared to,
+/// eg: `$ids` is an `array<record<user>>` in `WHERE id IN $ids`.
+fn infer_compared_parameter(
+    l: &Value,
+    o: &Operator,
+    r: &Value,
+    fields: &BTreeMap<String, Kind>,
+    state: &mut QueryState,
+) {
+    let (field, param, field_on_left) = match (l, r) {
+        (Value::Idiom(_), Value::Param(param)) => (l, param, true),
+        (Value::Param(param), Value::Idiom(_)) => (r, param, false),
+        _ => return,
+    };
+
+    if state.get(param.as_str()).is_some() || !is_document_field(field) {
+        return;
+    }
+
+    // fields which aren't defined can't be used to infer the parameter
+    let Ok(field_kind) = get_value_return_type(field, fields, state) else {
+        return;
+    };
+
+    if let Some(kind) = get_compared_kind(&field_kind, o, field_on_left) {
+        state.infer(param.as_str(), kind);
+    }
+}
+
+fn is_document_field(value: &Value) -> bool {
+    match value {
+        Value::Idiom(idiom) => matches!(idiom.0.first(), Some(Part::Field(_))),
+        _ => false,
+    }
+}
+
+/// Returns the kind of the value compared to a field, where `field_on_left` is whether
+/// the field is the left operand, eg: `field IN $values` rather than `$value IN field`.
+fn get_compared_kind(field_kind: &Kind, o: &Operator, field_on_left: bool) -> Option<Kind> {
+    let present = split_optional_kind(field_kind).0?;
+    let item = match &present {
+        Kind::Array(item, _) | Kind::Set(item, _) => Some(*item.clone()),
+        _ => None,
+    };
+
+    Some(match (o, field_on_left) {
+        (Operator::Equal | Operator::Exact | Operator::NotEqual, _) => field_kind.clone(),
+        (
+            Operator::LessThan
+            | Operator::LessThanOrEqual
+            | Operator::MoreThan
+            | Operator::MoreThanOrEqual,
+            _,
+        ) => present,
+        // eg: `tags ?= $tag`
+        (Operator::AnyEqual | Operator::AllEqual, true) => item?,
+        (Operator::Like | Operator::NotLike, _) => Kind::String,
+        (Operator::AnyLike | Operator::AllLike, true) => Kind::String,
+
+        // eg: `status IN $statuses`
+        (Operator::Inside | Operator::NotInside, true) => kind!(Arr present),
+        // eg: `tags ANYINSIDE $tags`
+        (Operator::AllInside | Operator::AnyInside | Operator::NoneInside, true) => match item {
+            Some(item) => kind!(Arr item),
+            None => kind!(Arr present),
+        },
+        // eg: `$tag IN tags` or `$search IN name`
+        (Operator::Inside | Operator::NotInside, false) => match (item, present) {
+            (Some(item), _) => item,
+            (None, Kind::String) => Kind::String,
+            _ => return None,
+        },
+
+        // eg: `tags CONTAINS $tag` or `name CONTAINS $search`
+        (Operator::Contain | Operator::NotContain, true) => match (item, present) {
+            (Some(item), _) => item,
+            (None, Kind::String) => Kind::String,
+            _ => return None,
+        },
+        // eg: `tags CONTAINSANY $tags`
+        (Operator::ContainAll | Operator::ContainAny | Operator::ContainNone, true) => {
+            kind!(Arr item?)
+        }
+        // eg: `$statuses CONTAINS status`
+        (Operator::Contain | Operator::NotContain, false) => kind!(Arr present),
+
+        _ => return None,
+    })
+}
@@ -0,0 +1,385 @@
+use std::collections::BTreeMap;
+
+use surrealdb::sql::{Idiom, Literal, Object, Operator, Part, Value};
+
+use crate::{
+    kind,
+    step_1_parse_sql::{FieldParsed, FieldType},
+    Kind,
+};
+
+use super::{
+    operator::get_arithmetic_return_type,
+    return_types::{get_value_or_infer_parameter_return_type, get_value_return_type},
+    schema::QueryState,
+    utils::{get_value_table, is_kind_assignable, split_optional_kind, union_kinds},
+};
+
+/// Interprets the `SET` clause of a `CREATE`, `UPDATE` or `UPSERT` statement,
+/// inferring the kind of any unknown parameters from the fields they are assigned to,
+/// eg: `$name` is a `string` in `SET name = $name`, and an `int` in `SET age += $years`,
+/// and validating that any other values can be assigned to the fields.
+///
+/// `READONLY` fields can only be set when the record is created, so `is_create` is `false`
+/// for statements which may update an existing record.
+pub fn interpret_set_expressions(
+    sets: &[(Idiom, Operator, Value)],
+    what: &[Value],
+    is_create: bool,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    let tables = get_data_tables(what, state)?;
+
+    // the values can reference the fields of the record being written, eg: `SET count = count + 1`
+    let field_types = match tables.first() {
+        Some(table_name) => state.table_select_fields(table_name)?,
+        None => BTreeMap::new(),
+    };
+
+    for (idiom, operator, value) in sets {
+        let mut kinds = Vec::new();
+        for table_name in tables.iter() {
+            let fields = &state.schema.schema.tables[table_name].fields;
+            let field_kind = get_set_field_kind(fields, idiom, is_create)?;
+            kinds.push(get_set_operator_kind(idiom, operator, field_kind)?);
+        }
+
+        let expected = union_kinds(kinds);
+        let kind =
+            get_value_or_infer_parameter_return_type(value, expected.clone(), &field_types, state)?;
+
+        if !is_kind_assignable(&kind, &expected) {
+            anyhow::bail!(
+                "Cannot assign `{}` to `{}` with `{}`, which expects `{}`",
+                kind,
+                idiom,
+                operator,
+                expected
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Interprets an object written to a table with `CONTENT`, `MERGE` or `INSERT`,
+/// inferring the kind of any unknown parameters from the fields they are assigned to,
+/// eg: `$name` is a `string` in `CONTENT { name: $name }`.
+///
+/// Keys which aren't defined on a `SCHEMAFULL` table are reported as errors.
+pub fn interpret_object_data(
+    object: &Object,
+    what: &[Value],
+    is_create: bool,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    // the schema is shared, so the table's fields can be borrowed while the state is mutated
+    let schema = state.schema.clone();
+
+    for table_name in get_data_tables(what, state)? {
+        let table = &schema.schema.tables[&table_name];
+
+        interpret_object_fields(
+            object,
+            &table.fields,
+            &ObjectData {
+                table: &table_name,
+                schemafull: table.schemafull,
+                is_create,
+            },
+            "",
+            state,
+        )?;
+    }
+
+    Ok(())
+}
+
+struct ObjectData<'a> {
+    table: &'a str,
+    schemafull: bool,
+    is_create: bool,
+}
+
+fn interpret_object_fields(
+    object: &Object,
+    fields: &BTreeMap<String, FieldParsed>,
+    data: &ObjectData,
+    path: &str,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    for (key, value) in object.iter() {
+        let path = match path {
+            "" => key.clone(),
+            path => format!("{}.{}", path, key),
+        };
+
+        let field = match fields.get(key) {
+            Some(field) => field,
+            None if data.schemafull => anyhow::bail!(
+                "Field `{}` does not exist on SCHEMAFULL table `{}`",
+                path,
+                data.table
+            ),
+            None => {
+                get_value_return_type(value, &BTreeMap::new(), state)?;
+                continue;
+            }
+        };
+
+        if field.has_override_value {
+            anyhow::bail!(
+                "Field `{}` has a `VALUE` clause which overrides any value set by the query",
+                path
+            );
+        }
+
+        // the `id` selects the record to write to, rather than updating it
+        if field.readonly && !data.is_create && path != "id" {
+            anyhow::bail!("Field `{}` is `READONLY` and cannot be updated", path);
+        }
+
+        match (&field.field_type, value) {
+            (FieldType::NestedObject(nested), Value::Object(object)) => {
+                interpret_object_fields(object, nested, data, &path, state)?
+            }
+            (FieldType::NestedArray(box FieldType::NestedObject(nested)), Value::Array(items)) => {
+                let item_kind = kind!(Obj get_nested_assign_kinds(nested)?);
+                for item in items.iter() {
+                    match item {
+                        Value::Object(object) => {
+                            interpret_object_fields(object, nested, data, &path, state)?
+                        }
+                        item => {
+                            get_value_or_infer_parameter_return_type(
+                                item,
+                                item_kind.clone(),
+                                &BTreeMap::new(),
+                                state,
+                            )?;
+                        }
+                    }
+                }
+            }
+            (_, value) => {
+                let kind = match data.is_create {
+                    true => field.compute_create_type()?,
+                    false => get_field_assign_kind(field)?,
+                };
+                interpret_value_with_kind(value, &kind, &path, state)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Infers the parameters in a value from the kind it is assigned to,
+/// recursing into object literals, eg: `{ source: $source }` assigned to `{ source: string }`.
+fn interpret_value_with_kind(
+    value: &Value,
+    kind: &Kind,
+    path: &str,
+    state: &mut QueryState,
+) -> Result<(), anyhow::Error> {
+    match (value, split_optional_kind(kind).0) {
+        (Value::Object(object), Some(Kind::Literal(Literal::Object(fields)))) => {
+            for (key, value) in object.iter() {
+                let path = format!("{}.{}", path, key);
+                match fields.get(key) {
+                    Some(kind) => interpret_value_with_kind(value, kind, &path, state)?,
+                    None => anyhow::bail!("Field `{}` does not exist", path),
+                }
+            }
+        }
+        _ => {
+            get_value_or_infer_parameter_return_type(value, kind.clone(), &BTreeMap::new(), state)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the tables which are written to, which must be defined in the schema
+fn get_data_tables(what: &[Value], state: &mut QueryState) -> Result<Vec<String>, anyhow::Error> {
+    let mut tables = Vec::new();
+    for value in what.iter() {
+        let table_name = get_value_table(value, state)?;
+        if !state.schema.schema.tables.contains_key(&table_name) {
+            anyhow::bail!(
+                "Tried to write fields on an unknown or view table: {}",
+                table_name
+            );
+        }
+        tables.push(table_name);
+    }
+    Ok(tables)
+}
+
+/// Returns the kind which can be assigned to the field at the path, eg: `address.city`
+fn get_set_field_kind(
+    fields: &BTreeMap<String, FieldParsed>,
+    idiom: &Idiom,
+    is_create: bool,
+) -> Result<Kind, anyhow::Error> {
+    let mut fields = fields;
+    let mut parts = idiom.0.as_slice();
+
+    loop {
+        let name = match parts.first() {
+            Some(Part::Field(name)) => name.as_str(),
+            _ => anyhow::bail!("Unsupported path in `SET` clause: `{}`", idiom),
+        };
+
+        let field = match fields.get(name) {
+            Some(field) => field,
+            // an undefined field on a schemaless table can be any value
+            None => return Ok(Kind::Any),
+        };
+
+        if field.has_override_value {
+            anyhow::bail!(
+                "Field `{}` has a `VALUE` clause which overrides any value set by the query",
+                idiom
+            );
+        }
+
+        if field.readonly && !is_create {
+            anyhow::bail!("Field `{}` is `READONLY` and cannot be updated", idiom);
+        }
+
+        parts = &parts[1..];
+
+        match (&field.field_type, parts.first()) {
+            (_, None) => return get_field_assign_kind(field),
+            (FieldType::NestedObject(nested), Some(Part::Field(_))) => fields = nested,
+            (
+                FieldType::NestedArray(box FieldType::NestedObject(nested)),
+                Some(Part::All | Part::Index(_) | Part::First | Part::Last),
+            ) if parts.len() > 1 => {
+                parts = &parts[1..];
+                fields = nested;
+            }
+            (
+                FieldType::NestedArray(box FieldType::NestedObject(nested)),
+                Some(Part::All | Part::Index(_) | Part::First | Part::Last),
+            ) => {
+                return Ok(kind!(Obj get_nested_assign_kinds(nested)?));
+            }
+            (FieldType::Simple, Some(_)) => {
+                return get_kind_at_path(&field.return_type, parts, idiom)
+            }
+            (
+                FieldType::NestedArray(box FieldType::Simple),
+                Some(Part::All | Part::Index(_) | Part::First | Part::Last),
+            ) => return get_kind_at_path(&field.return_type, &parts[1..], idiom),
+            _ => anyhow::bail!("Unsupported path in `SET` clause: `{}`", idiom),
+        }
+    }
+}
+
+fn get_field_assign_kind(field: &FieldParsed) -> Result<Kind, anyhow::Error> {
+    let kind = match &field.field_type {
+        FieldType::Simple => field.return_type.clone(),
+        FieldType::NestedObject(nested) => kind!(Obj get_nested_assign_kinds(nested)?),
+        FieldType::NestedArray(box FieldType::NestedObject(nested)) => {
+            kind!(Arr kind!(Obj get_nested_assign_kinds(nested)?))
+        }
+        FieldType::NestedArray(_) => kind!(Arr field.return_type.clone()),
+    };
+
+    Ok(match field.is_optional {
+        true => Kind::Option(Box::new(kind)),
+        false => kind,
+    })
+}
+
+fn get_nested_assign_kinds(
+    fields: &BTreeMap<String, FieldParsed>,
+) -> Result<BTreeMap<String, Kind>, anyhow::Error> {
+    let mut kinds = BTreeMap::new();
+    for (key, field) in fields {
+        if !field.has_override_value {
+            kinds.insert(key.clone(), field.compute_create_type()?);
+        }
+    }
+    Ok(kinds)
+}
+
+/// Returns the kind at the path within a field's declared type, eg: `meta.source` of `{ source: string }`
+fn get_kind_at_path(kind: &Kind, parts: &[Part], idiom: &Idiom) -> Result<Kind, anyhow::Error> {
+    Ok(match (kind, parts.first()) {
+        (kind, None) => kind.clone(),
+        (Kind::Option(inner), _) => get_kind_at_path(inner, parts, idiom)?,
+        (Kind::Any | Kind::Object, _) => Kind::Any,
+        (Kind::Literal(Literal::Object(fields)), Some(Part::Field(name))) => {
+            match fields.get(name.as_str()) {
+                Some(kind) => get_kind_at_path(kind, &parts[1..], idiom)?,
+                None => anyhow::bail!("Field `{}` does not exist", idiom),
+            }
+        }
+        (Kind::Array(inner, _), Some(Part::All | Part::Index(_) | Part::First | Part::Last)) => {
+            get_kind_at_path(inner, &parts[1..], idiom)?
+        }
+        (kind, _) => anyhow::bail!("Cannot set `{}` on a field of type `{}`", idiom, kind),
+    })
+}
+
+/// Returns the kind of the value which can be used with an operator in a `SET` clause,
+/// eg: `SET tags += $tag` accepts a single item or an array of items,
+/// and `SET expires += $duration` accepts a `duration` for a `datetime` field.
+fn get_set_operator_kind(
+    idiom: &Idiom,
+    operator: &Operator,
+    field_kind: Kind,
+) -> Result<Kind, anyhow::Error> {
+    let kind = match operator {
+        Operator::Equal => return Ok(field_kind),
+        Operator::Inc | Operator::Dec | Operator::Ext => {
+            split_optional_kind(&field_kind).0.unwrap_or(Kind::Any)
+        }
+        _ => anyhow::bail!("Unsupported operator `{}` in `SET` clause", operator),
+    };
+
+    Ok(match (operator, kind) {
+        (_, Kind::Any) => Kind::Any,
+        (_, Kind::Array(inner, size)) => {
+            union_kinds(vec![*inner.clone(), Kind::Array(inner, size)])
+        }
+        (Operator::Inc | Operator::Dec, kind) => {
+            // `+=` and `-=` are the same as `field = field + value`, so the result must fit the field
+            let arithmetic = match operator {
+                Operator::Inc => Operator::Add,
+                _ => Operator::Sub,
+            };
+            let is_valid_operand = |operand: &Kind| {
+                get_arithmetic_return_type(&arithmetic, &kind, operand)
+                    .is_ok_and(|result| is_kind_assignable(&result, &kind))
+            };
+
+            // the operand is usually the same kind as the field, eg: `int`, `string` or `duration`,
+            // otherwise it is a different kind such as a `duration` for a `datetime` field
+            match is_valid_operand(&kind) {
+                true => kind,
+                false => match [Kind::Duration, Kind::Number]
+                    .into_iter()
+                    .find(|operand| is_valid_operand(operand))
+                {
+                    Some(operand) => operand,
+                    None => anyhow::bail!(
+                        "`{}` cannot be used with `{}` of type `{}`",
+                        operator,
+                        idiom,
+                        kind
+                    ),
+                },
+            }
+        }
+        (_, kind) => anyhow::bail!(
+            "`{}` can only be used with array fields, but `{}` is `{}`",
+            operator,
+            idiom,
+            kind
+        ),
+    })
+}
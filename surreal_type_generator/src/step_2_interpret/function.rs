@@ -1,32 +1,527 @@
-use surrealdb::sql::{Function, Value};
+use std::collections::BTreeMap;
 
-use crate::Kind;
+use surrealdb::sql::{Closure, Function, Literal, Value};
 
-use super::QueryState;
+use crate::{kind, Kind};
+
+use super::{
+    get_value_return_type,
+    return_types::{get_field_from_paths, get_value_or_infer_parameter_return_type},
+    utils::{is_kind_assignable, split_optional_kind, union_kinds},
+    QueryState,
+};
 
 pub fn get_function_return_type(
     state: &mut QueryState,
     func: &Function,
+    field_types: &BTreeMap<String, Kind>,
 ) -> Result<Kind, anyhow::Error> {
     match func {
-        Function::Custom(name, values) => get_custom_function_return_type(state, name, values),
-        Function::Normal(name, ..) => normal_function_return_type(name),
+        Function::Custom(name, values) => {
+            get_custom_function_return_type(state, name, values, field_types)
+        }
+        Function::Normal(name, values) => {
+            if let Some(kind) =
+                get_literal_type_function_return_type(state, name, values, field_types)?
+            {
+                return Ok(kind);
+            }
+            let args = get_normal_function_argument_types(state, name, values, field_types)?;
+            match name.as_str() {
+                "meta::id" | "record::id" => {
+                    get_record_id_value_return_type(state, name, nth_argument(name, &args, 0)?)
+                }
+                _ => normal_function_return_type(name, &args),
+            }
+        }
         Function::Script(..) => anyhow::bail!("Script functions are not yet supported"),
         _ => anyhow::bail!("Unsupported function: {}", func),
     }
 }
 
+/// Returns the return type of a custom function, eg: `fn::foo($bar)`, inferring any unknown parameters
+/// passed as arguments from the function's declared arguments, and validating the kinds of the others.
 pub fn get_custom_function_return_type(
     state: &mut QueryState,
     name: &str,
-    _values: &[Value],
+    values: &[Value],
+    field_types: &BTreeMap<String, Kind>,
 ) -> Result<Kind, anyhow::Error> {
     let function = state.function(name)?;
 
+    // trailing optional arguments can be omitted
+    let required = function.args.len()
+        - function
+            .args
+            .iter()
+            .rev()
+            .take_while(|(_, kind)| split_optional_kind(kind).1)
+            .count();
+
+    if values.len() < required || values.len() > function.args.len() {
+        anyhow::bail!(
+            "`fn::{}` expects {} arguments, but got {}",
+            name,
+            match required == function.args.len() {
+                true => required.to_string(),
+                false => format!("{} to {}", required, function.args.len()),
+            },
+            values.len()
+        );
+    }
+
+    for (index, (value, (_, expected))) in values.iter().zip(function.args.iter()).enumerate() {
+        let kind =
+            get_value_or_infer_parameter_return_type(value, expected.clone(), field_types, state)?;
+
+        if !is_kind_assignable(&kind, expected) {
+            anyhow::bail!(
+                "Argument {} of `fn::{}` expects `{}`, but got `{}`",
+                index + 1,
+                name,
+                expected,
+                kind
+            );
+        }
+    }
+
     Ok(function.return_type)
 }
 
-pub fn normal_function_return_type(name: &str) -> Result<Kind, anyhow::Error> {
+/// Returns the more specific return type of `type::` functions called with string literal arguments,
+/// eg: `type::thing("user", $id)` returns `record<user>`, and `type::field("name")` returns the type of the `name` field.
+fn get_literal_type_function_return_type(
+    state: &mut QueryState,
+    name: &str,
+    values: &[Value],
+    field_types: &BTreeMap<String, Kind>,
+) -> Result<Option<Kind>, anyhow::Error> {
+    Ok(Some(match (name, values) {
+        ("type::thing", [Value::Strand(table), id]) => {
+            let table = table.as_str();
+            match id {
+                // `type::thing("user", $id)` infers `$id` as the id value of the table
+                Value::Param(param) if state.get(param.as_str()).is_none() => {
+                    let id_value_type = match state.schema.schema.tables.get(table) {
+                        Some(table) => table.id_value_type.clone(),
+                        None => anyhow::bail!("Unknown table: {}", table),
+                    };
+                    state.infer(param.as_str(), id_value_type);
+                }
+                id => {
+                    get_value_return_type(id, field_types, state)?;
+                }
+            }
+            Kind::Record(vec![table.into()])
+        }
+        ("type::record", [record, Value::Strand(table)]) => {
+            let table = table.as_str();
+            match record {
+                Value::Param(param) if state.get(param.as_str()).is_none() => {
+                    state.infer(param.as_str(), Kind::Record(vec![table.into()]));
+                }
+                record => {
+                    get_value_return_type(record, field_types, state)?;
+                }
+            }
+            Kind::Record(vec![table.into()])
+        }
+        ("type::field", [Value::Strand(path)]) => {
+            let idiom = surrealdb::sql::idiom(path.as_str())?;
+            get_field_from_paths(&idiom.0, field_types, state)?
+        }
+        ("type::fields", [Value::Array(paths)])
+            if paths.iter().all(|path| matches!(path, Value::Strand(_))) =>
+        {
+            let mut kinds = Vec::new();
+            for path in paths.iter() {
+                if let Value::Strand(path) = path {
+                    let idiom = surrealdb::sql::idiom(path.as_str())?;
+                    kinds.push(get_field_from_paths(&idiom.0, field_types, state)?);
+                }
+            }
+            kind!(Arr union_kinds(kinds))
+        }
+        _ => return Ok(None),
+    }))
+}
+
+/// Returns the type of the id value of a record, eg: `meta::id(user:1)` returns the type of `user`'s `id` field.
+fn get_record_id_value_return_type(
+    state: &QueryState,
+    name: &str,
+    record: &Kind,
+) -> Result<Kind, anyhow::Error> {
+    Ok(match record {
+        Kind::Record(tables) if !tables.is_empty() => {
+            let mut kinds = Vec::new();
+            for table in tables {
+                kinds.push(match state.schema.schema.tables.get(table.as_str()) {
+                    Some(table) => table.id_value_type.clone(),
+                    // views can be grouped by any value
+                    None => Kind::Any,
+                });
+            }
+            union_kinds(kinds)
+        }
+        Kind::Record(_) | Kind::Any => Kind::Any,
+        Kind::Option(inner) => kind!(Opt(get_record_id_value_return_type(state, name, inner)?)),
+        kind => anyhow::bail!("Function `{}` expects a record, got: {}", name, kind),
+    })
+}
+
+/// Returns the kinds of the arguments passed to a built-in function.
+/// Closure arguments, eg: `array::map($items, |$item| $item.name)`, are represented by the kind they return.
+fn get_normal_function_argument_types(
+    state: &mut QueryState,
+    name: &str,
+    values: &[Value],
+    field_types: &BTreeMap<String, Kind>,
+) -> Result<Vec<Kind>, anyhow::Error> {
+    let mut args = Vec::new();
+
+    for (index, value) in values.iter().enumerate() {
+        let kind = match value {
+            Value::Closure(closure) => {
+                let closure_args = closure_argument_types(name, &args)?;
+                get_closure_return_type(state, closure, &closure_args, field_types)?
+            }
+            // unknown parameters are inferred from the function's signature, eg: `string::lowercase($value)`
+            value => match normal_function_argument_type(name, index) {
+                Some(kind) => {
+                    get_value_or_infer_parameter_return_type(value, kind, field_types, state)?
+                }
+                None => get_value_return_type(value, field_types, state)?,
+            },
+        };
+        args.push(kind);
+    }
+
+    Ok(args)
+}
+
+/// Returns the kind a built-in function expects for its nth argument, used to infer parameters passed to it.
+/// Returns `None` for arguments which accept any value, or functions we don't know the signature of.
+fn normal_function_argument_type(name: &str, index: usize) -> Option<Kind> {
+    let number_array = || kind!(Arr Kind::Number);
+
+    Some(match (name, index) {
+        // `string::` functions
+        ("string::repeat", 1) | ("string::slice", 1 | 2) => Kind::Int,
+        (
+            "string::semver::set::major"
+            | "string::semver::set::minor"
+            | "string::semver::set::patch",
+            1,
+        ) => Kind::Int,
+        ("string::concat", _) => return None,
+        (name, _) if name.starts_with("string::") => Kind::String,
+
+        // `array::` functions
+        ("array::range" | "array::repeat", _) => return None,
+        (
+            "array::combine" | "array::complement" | "array::concat" | "array::difference"
+            | "array::intersect" | "array::logical_and" | "array::logical_or"
+            | "array::logical_xor" | "array::union",
+            _,
+        ) => kind!(Arr Kind::Any),
+        ("array::join", 1) => Kind::String,
+        (name, 0) if name.starts_with("array::") => kind!(Arr Kind::Any),
+
+        // `math::` functions, which aggregate an array of numbers or transform a single number
+        (
+            "math::bottom"
+            | "math::interquartile"
+            | "math::max"
+            | "math::mean"
+            | "math::median"
+            | "math::midhinge"
+            | "math::min"
+            | "math::mode"
+            | "math::nearestrank"
+            | "math::percentile"
+            | "math::product"
+            | "math::spread"
+            | "math::stddev"
+            | "math::sum"
+            | "math::top"
+            | "math::trimean"
+            | "math::variance",
+            0,
+        ) => number_array(),
+        (name, _) if name.starts_with("math::") => Kind::Number,
+
+        // `time::` functions
+        ("time::max" | "time::min", 0) => kind!(Arr Kind::Datetime),
+        ("time::floor" | "time::round", 1) => Kind::Duration,
+        ("time::format" | "time::group", 1) => Kind::String,
+        (name, _) if name.starts_with("time::from::") => Kind::Number,
+        (name, 0) if name.starts_with("time::") => Kind::Datetime,
+
+        // `duration::` functions
+        (name, _) if name.starts_with("duration::from::") => Kind::Number,
+        (name, 0) if name.starts_with("duration::") => Kind::Duration,
+
+        ("encoding::base64::encode", 0) => Kind::Bytes,
+        ("encoding::base64::decode", 0) => Kind::String,
+        (name, _) if name.starts_with("crypto::") || name.starts_with("parse::") => Kind::String,
+
+        // `object::` functions
+        ("object::from_entries", 0) => kind!(Arr Kind::Any),
+        (name, 0) if name.starts_with("object::") => Kind::Object,
+
+        // `vector::` functions
+        ("vector::scale", 1) | ("vector::distance::minkowski", 2) => Kind::Number,
+        (name, _) if name.starts_with("vector::") => number_array(),
+
+        // `meta::` and `record::` functions
+        (
+            "meta::id" | "meta::tb" | "record::exists" | "record::id" | "record::table"
+            | "record::tb",
+            0,
+        ) => Kind::Record(vec![]),
+
+        _ => return None,
+    })
+}
+
+/// The kinds of the arguments a built-in function calls a closure with, based on the arguments before it.
+fn closure_argument_types(name: &str, args: &[Kind]) -> Result<Vec<Kind>, anyhow::Error> {
+    let item = array_item_type(name, nth_argument(name, args, 0)?)?;
+
+    Ok(match name {
+        // `|$accumulator, $value, $index|`
+        "array::fold" => vec![nth_argument(name, args, 1)?.clone(), item, Kind::Number],
+        "array::reduce" => vec![item.clone(), item, Kind::Number],
+        // `|$value, $index|`
+        _ => vec![item, Kind::Number],
+    })
+}
+
+fn get_closure_return_type(
+    state: &mut QueryState,
+    closure: &Closure,
+    arg_types: &[Kind],
+    field_types: &BTreeMap<String, Kind>,
+) -> Result<Kind, anyhow::Error> {
+    state.push_stack_frame();
+
+    for (index, (ident, kind)) in closure.args.iter().enumerate() {
+        let kind = match (kind, arg_types.get(index)) {
+            // untyped closure arguments are inferred from what the function calls it with
+            (Kind::Any, Some(arg_type)) => arg_type.clone(),
+            (kind, _) => kind.clone(),
+        };
+        state.set_local(ident.as_str(), kind);
+    }
+
+    let return_type = match &closure.returns {
+        Some(kind) => Ok(kind.clone()),
+        None => get_value_return_type(&closure.body, field_types, state),
+    };
+
+    state.pop_stack_frame();
+
+    return_type
+}
+
+fn nth_argument<'a>(name: &str, args: &'a [Kind], index: usize) -> Result<&'a Kind, anyhow::Error> {
+    match args.get(index) {
+        Some(kind) => Ok(kind),
+        None => anyhow::bail!(
+            "Function `{}` expects at least {} argument(s)",
+            name,
+            index + 1
+        ),
+    }
+}
+
+/// Returns the kind of the items in an array passed to a built-in function.
+fn array_item_type(name: &str, kind: &Kind) -> Result<Kind, anyhow::Error> {
+    Ok(match kind {
+        Kind::Array(item, ..) | Kind::Set(item, ..) => *item.clone(),
+        Kind::Literal(Literal::Array(items)) => union_kinds(items.clone()),
+        Kind::Option(inner) => array_item_type(name, inner)?,
+        Kind::Either(kinds) => {
+            let mut items = Vec::new();
+            for kind in kinds {
+                items.push(array_item_type(name, kind)?);
+            }
+            union_kinds(items)
+        }
+        Kind::Any => Kind::Any,
+        kind => anyhow::bail!("Function `{}` expects an array, got: {}", name, kind),
+    })
+}
+
+/// Flattens one level of nested arrays, leaving any non-array items as they are.
+fn flattened_item_type(name: &str, item: Kind) -> Result<Kind, anyhow::Error> {
+    let kinds = match item {
+        Kind::Either(kinds) => kinds,
+        kind => vec![kind],
+    };
+
+    let mut items = Vec::new();
+    for kind in kinds {
+        items.push(match kind {
+            Kind::Array(..) | Kind::Set(..) | Kind::Literal(Literal::Array(_)) => {
+                array_item_type(name, &kind)?
+            }
+            kind => kind,
+        });
+    }
+
+    Ok(union_kinds(items))
+}
+
+fn array_function_return_type(name: &str, args: &[Kind]) -> Result<Kind, anyhow::Error> {
+    // `array::range` and `array::repeat` are the only functions which don't take an array first
+    match name {
+        "array::range" => return Ok(kind!(Arr Kind::Number)),
+        "array::repeat" => return Ok(kind!(Arr nth_argument(name, args, 0)?.clone())),
+        _ => {}
+    }
+
+    let item = array_item_type(name, nth_argument(name, args, 0)?)?;
+
+    Ok(match name {
+        // functions returning an array of the same items
+        "array::complement" | "array::distinct" | "array::filter" | "array::intersect"
+        | "array::remove" | "array::reverse" | "array::shuffle" | "array::slice"
+        | "array::sort" | "array::sort::asc" | "array::sort::desc" | "array::swap" => {
+            kind!(Arr item)
+        }
+
+        // functions returning a single item, which is `NONE` if the array is empty or no item matches
+        "array::at" | "array::find" | "array::first" | "array::last" | "array::max"
+        | "array::min" | "array::pop" => kind!(Opt(item)),
+
+        // functions adding a value, or an array of values, to the array
+        "array::add" => {
+            let value = nth_argument(name, args, 1)?;
+            let value = flattened_item_type(name, value.clone())?;
+            kind!(Arr union_kinds([item, value]))
+        }
+        "array::append" | "array::fill" | "array::insert" | "array::prepend" | "array::push" => {
+            kind!(Arr union_kinds([item, nth_argument(name, args, 1)?.clone()]))
+        }
+
+        // functions combining the items of multiple arrays
+        "array::concat" | "array::difference" | "array::logical_and" | "array::logical_or"
+        | "array::logical_xor" | "array::union" => {
+            let mut items = vec![item];
+            for arg in &args[1..] {
+                items.push(array_item_type(name, arg)?);
+            }
+            kind!(Arr union_kinds(items))
+        }
+        "array::combine" => {
+            let other = array_item_type(name, nth_argument(name, args, 1)?)?;
+            kind!(Arr kind!(Arr union_kinds([item, other])))
+        }
+        "array::clump" | "array::windows" => kind!(Arr kind!(Arr item)),
+        "array::flatten" | "array::group" => kind!(Arr flattened_item_type(name, item)?),
+        "array::transpose" => kind!(Arr kind!(Arr flattened_item_type(name, item)?)),
+
+        // functions calling a closure
+        "array::map" => kind!(Arr nth_argument(name, args, 1)?.clone()),
+        "array::fold" => union_kinds([
+            nth_argument(name, args, 1)?.clone(),
+            nth_argument(name, args, 2)?.clone(),
+        ]),
+        "array::reduce" => kind!(Opt(union_kinds([
+            item,
+            nth_argument(name, args, 1)?.clone()
+        ]))),
+
+        "array::boolean_and" | "array::boolean_not" | "array::boolean_or"
+        | "array::boolean_xor" | "array::matches" => kind!(Arr Kind::Bool),
+        "array::filter_index" => kind!(Arr Kind::Number),
+        "array::find_index" | "array::index_of" => kind!(Opt(Kind::Number)),
+        "array::all" | "array::any" | "array::every" | "array::includes" | "array::is_empty"
+        | "array::some" => Kind::Bool,
+        "array::join" => Kind::String,
+        "array::len" => Kind::Number,
+
+        _ => anyhow::bail!("Unsupported normal function: {}", name),
+    })
+}
+
+/// Returns the key and value kinds of an object passed to a built-in function,
+/// or `None` if the shape of the object is unknown.
+fn object_fields_type<'a>(
+    name: &str,
+    kind: &'a Kind,
+) -> Result<Option<&'a BTreeMap<String, Kind>>, anyhow::Error> {
+    Ok(match kind {
+        Kind::Literal(Literal::Object(fields)) => Some(fields),
+        Kind::Option(inner) => object_fields_type(name, inner)?,
+        Kind::Object | Kind::Any => None,
+        kind => anyhow::bail!("Function `{}` expects an object, got: {}", name, kind),
+    })
+}
+
+fn object_function_return_type(name: &str, args: &[Kind]) -> Result<Kind, anyhow::Error> {
+    let arg = nth_argument(name, args, 0)?;
+
+    if name == "object::from_entries" {
+        let mut fields = BTreeMap::new();
+        let entries = match array_item_type(name, arg)? {
+            Kind::Either(kinds) => kinds,
+            kind => vec![kind],
+        };
+        for entry in entries {
+            match entry {
+                Kind::Literal(Literal::Array(entry)) => match entry.as_slice() {
+                    [Kind::Literal(Literal::String(key)), value] => {
+                        fields.insert(key.as_str().to_string(), value.clone());
+                    }
+                    _ => return Ok(Kind::Object),
+                },
+                _ => return Ok(Kind::Object),
+            }
+        }
+        return Ok(kind!(Obj fields));
+    }
+
+    let fields = object_fields_type(name, arg)?;
+
+    Ok(match name {
+        "object::entries" => kind!(Arr match fields {
+            Some(fields) => union_kinds(fields.iter().map(|(key, value)| {
+                Kind::Literal(Literal::Array(vec![
+                    Kind::Literal(Literal::String(key.as_str().into())),
+                    value.clone(),
+                ]))
+            })),
+            None => Kind::Literal(Literal::Array(vec![Kind::String, Kind::Any])),
+        }),
+        "object::keys" => kind!(Arr match fields {
+            Some(fields) => union_kinds(
+                fields
+                    .keys()
+                    .map(|key| Kind::Literal(Literal::String(key.as_str().into())))
+            ),
+            None => Kind::String,
+        }),
+        "object::values" => kind!(Arr match fields {
+            Some(fields) => union_kinds(fields.values().cloned()),
+            None => Kind::Any,
+        }),
+        "object::len" => Kind::Int,
+        _ => anyhow::bail!("Unsupported normal function: {}", name),
+    })
+}
+
+pub fn normal_function_return_type(name: &str, args: &[Kind]) -> Result<Kind, anyhow::Error> {
+    if name.starts_with("array::") {
+        return array_function_return_type(name, args);
+    }
+
+    if name.starts_with("object::") {
+        return object_function_return_type(name, args);
+    }
+
     Ok(match name {
         "count" => Kind::Number,
 
@@ -138,24 +633,204 @@ pub fn normal_function_return_type(name: &str) -> Result<Kind, anyhow::Error> {
         "crypto::scrypt::compare" => Kind::Bool,
         "crypto::scrypt::generate" => Kind::String,
 
-        // `meta::` functions
-        "meta::id" => Kind::Any, // TODO: should this be a string?
+        // `string::` functions
+        "string::concat" => Kind::String,
+        "string::contains" => Kind::Bool,
+        "string::ends_with" => Kind::Bool,
+        "string::join" => Kind::String,
+        "string::len" => Kind::Int,
+        "string::lowercase" => Kind::String,
+        "string::matches" => Kind::Bool,
+        "string::repeat" => Kind::String,
+        "string::replace" => Kind::String,
+        "string::reverse" => Kind::String,
+        "string::slice" => Kind::String,
+        "string::slug" => Kind::String,
+        "string::split" => Kind::Array(Box::new(Kind::String), None),
+        "string::starts_with" => Kind::Bool,
+        "string::trim" => Kind::String,
+        "string::uppercase" => Kind::String,
+        "string::words" => Kind::Array(Box::new(Kind::String), None),
+        "string::distance::damerau_levenshtein" => Kind::Number,
+        "string::distance::hamming" => Kind::Number,
+        "string::distance::levenshtein" => Kind::Number,
+        "string::distance::normalized_damerau_levenshtein" => Kind::Number,
+        "string::distance::normalized_levenshtein" => Kind::Number,
+        "string::distance::osa_distance" => Kind::Number,
+        "string::html::encode" => Kind::String,
+        "string::html::sanitize" => Kind::String,
+        "string::is::alpha" => Kind::Bool,
+        "string::is::alphanum" => Kind::Bool,
+        "string::is::ascii" => Kind::Bool,
+        "string::is::datetime" => Kind::Bool,
+        "string::is::domain" => Kind::Bool,
+        "string::is::email" => Kind::Bool,
+        "string::is::hexadecimal" => Kind::Bool,
+        "string::is::ip" => Kind::Bool,
+        "string::is::ipv4" => Kind::Bool,
+        "string::is::ipv6" => Kind::Bool,
+        "string::is::latitude" => Kind::Bool,
+        "string::is::longitude" => Kind::Bool,
+        "string::is::numeric" => Kind::Bool,
+        "string::is::record" => Kind::Bool,
+        "string::is::semver" => Kind::Bool,
+        "string::is::ulid" => Kind::Bool,
+        "string::is::url" => Kind::Bool,
+        "string::is::uuid" => Kind::Bool,
+        "string::semver::compare" => Kind::Int,
+        "string::semver::major" => Kind::Int,
+        "string::semver::minor" => Kind::Int,
+        "string::semver::patch" => Kind::Int,
+        "string::semver::inc::major" => Kind::String,
+        "string::semver::inc::minor" => Kind::String,
+        "string::semver::inc::patch" => Kind::String,
+        "string::semver::set::major" => Kind::String,
+        "string::semver::set::minor" => Kind::String,
+        "string::semver::set::patch" => Kind::String,
+        "string::similarity::fuzzy" => Kind::Number,
+        "string::similarity::jaro" => Kind::Number,
+        "string::similarity::jaro_winkler" => Kind::Number,
+        "string::similarity::smithwaterman" => Kind::Number,
+        "string::similarity::sorensen_dice" => Kind::Number,
+
+        // `type::` functions
+        "type::array" => match args.first() {
+            Some(kind @ (Kind::Array(..) | Kind::Set(..) | Kind::Literal(Literal::Array(_)))) => {
+                kind.clone()
+            }
+            _ => Kind::Array(Box::new(Kind::Any), None),
+        },
+        "type::bool" => Kind::Bool,
+        "type::bytes" => Kind::Bytes,
+        "type::datetime" => Kind::Datetime,
+        "type::decimal" => Kind::Decimal,
+        "type::duration" => Kind::Duration,
+        "type::field" => Kind::Any,
+        "type::fields" => Kind::Array(Box::new(Kind::Any), None),
+        "type::float" => Kind::Float,
+        "type::geometry" => Kind::Geometry(vec![]),
+        "type::int" => Kind::Int,
+        "type::number" => Kind::Number,
+        "type::point" => Kind::Point,
+        "type::range" => Kind::Range,
+        "type::record" => Kind::Record(vec![]),
+        "type::string" => Kind::String,
+        "type::table" => Kind::String,
+        "type::thing" => Kind::Record(vec![]),
+        "type::uuid" => Kind::Uuid,
+        "type::is::array" => Kind::Bool,
+        "type::is::bool" => Kind::Bool,
+        "type::is::bytes" => Kind::Bool,
+        "type::is::collection" => Kind::Bool,
+        "type::is::datetime" => Kind::Bool,
+        "type::is::decimal" => Kind::Bool,
+        "type::is::duration" => Kind::Bool,
+        "type::is::float" => Kind::Bool,
+        "type::is::geometry" => Kind::Bool,
+        "type::is::int" => Kind::Bool,
+        "type::is::line" => Kind::Bool,
+        "type::is::multiline" => Kind::Bool,
+        "type::is::multipoint" => Kind::Bool,
+        "type::is::multipolygon" => Kind::Bool,
+        "type::is::none" => Kind::Bool,
+        "type::is::null" => Kind::Bool,
+        "type::is::number" => Kind::Bool,
+        "type::is::object" => Kind::Bool,
+        "type::is::point" => Kind::Bool,
+        "type::is::polygon" => Kind::Bool,
+        "type::is::record" => Kind::Bool,
+        "type::is::string" => Kind::Bool,
+        "type::is::uuid" => Kind::Bool,
+
+        // `encoding::` functions
+        "encoding::base64::decode" => Kind::Bytes,
+        "encoding::base64::encode" => Kind::String,
+
+        // `parse::` functions
+        "parse::email::host" => Kind::Option(Box::new(Kind::String)),
+        "parse::email::user" => Kind::Option(Box::new(Kind::String)),
+        "parse::url::domain" => Kind::Option(Box::new(Kind::String)),
+        "parse::url::fragment" => Kind::Option(Box::new(Kind::String)),
+        "parse::url::host" => Kind::Option(Box::new(Kind::String)),
+        "parse::url::path" => Kind::Option(Box::new(Kind::String)),
+        "parse::url::port" => Kind::Option(Box::new(Kind::Int)),
+        "parse::url::query" => Kind::Option(Box::new(Kind::String)),
+        "parse::url::scheme" => Kind::Option(Box::new(Kind::String)),
+
+        // `rand::` functions
+        "rand" => Kind::Float,
+        "rand::bool" => Kind::Bool,
+        "rand::enum" => union_kinds(args.to_vec()),
+        "rand::float" => Kind::Float,
+        "rand::guid" => Kind::String,
+        "rand::int" => Kind::Int,
+        "rand::string" => Kind::String,
+        "rand::time" => Kind::Datetime,
+        "rand::ulid" => Kind::String,
+        "rand::uuid" => Kind::Uuid,
+        "rand::uuid::v4" => Kind::Uuid,
+        "rand::uuid::v7" => Kind::Uuid,
+
+        // `search::` functions
+        "search::analyze" => Kind::Array(Box::new(Kind::String), None),
+        "search::highlight" => Kind::String,
+        "search::offsets" => Kind::Object,
+        "search::score" => Kind::Number,
+
+        // `session::` functions
+        "session::ac" => Kind::Option(Box::new(Kind::String)),
+        "session::db" => Kind::String,
+        "session::id" => Kind::Option(Box::new(Kind::String)),
+        "session::ip" => Kind::Option(Box::new(Kind::String)),
+        "session::ns" => Kind::String,
+        "session::origin" => Kind::Option(Box::new(Kind::String)),
+        "session::rd" => Kind::Option(Box::new(Kind::Record(vec![]))),
+        "session::token" => Kind::Option(Box::new(Kind::Object)),
+
+        // `geo::` functions
+        "geo::area" => Kind::Number,
+        "geo::bearing" => Kind::Number,
+        "geo::centroid" => Kind::Geometry(vec!["point".into()]),
+        "geo::distance" => Kind::Number,
+        "geo::hash::decode" => Kind::Geometry(vec!["point".into()]),
+        "geo::hash::encode" => Kind::String,
+        "geo::is::valid" => Kind::Bool,
+
+        // `vector::` functions
+        "vector::add" => Kind::Array(Box::new(Kind::Number), None),
+        "vector::angle" => Kind::Number,
+        "vector::cross" => Kind::Array(Box::new(Kind::Number), None),
+        "vector::divide" => Kind::Array(Box::new(Kind::Number), None),
+        "vector::dot" => Kind::Number,
+        "vector::magnitude" => Kind::Number,
+        "vector::multiply" => Kind::Array(Box::new(Kind::Number), None),
+        "vector::normalize" => Kind::Array(Box::new(Kind::Number), None),
+        "vector::project" => Kind::Array(Box::new(Kind::Number), None),
+        "vector::scale" => Kind::Array(Box::new(Kind::Number), None),
+        "vector::subtract" => Kind::Array(Box::new(Kind::Number), None),
+        "vector::distance::chebyshev" => Kind::Number,
+        "vector::distance::euclidean" => Kind::Number,
+        "vector::distance::hamming" => Kind::Number,
+        // the distance computed by the `<|k|>` operator in the `WHERE` clause
+        "vector::distance::knn" => Kind::Number,
+        "vector::distance::mahalanobis" => Kind::Number,
+        "vector::distance::manhattan" => Kind::Number,
+        "vector::distance::minkowski" => Kind::Number,
+        "vector::similarity::cosine" => Kind::Number,
+        "vector::similarity::jaccard" => Kind::Number,
+        "vector::similarity::pearson" => Kind::Number,
+        "vector::similarity::spearman" => Kind::Number,
+
+        // `meta::` and `record::` functions, `meta::id` and `record::id` are resolved from the table's id type
+        "meta::tb" => Kind::String,
         "meta::type" => Kind::String,
+        "record::exists" => Kind::Bool,
+        "record::table" => Kind::String,
+        "record::tb" => Kind::String,
 
         // TODO: add more functions
-        // - `array::`
-        // - `encoding::`
-        // - `geo::`
         // - `http::`
-        // - `object::`
-        // - `parse::`
-        // - `rand::`
-        // - `search::`
-        // - `session::`
         // - `sleep::`
-        // - `string::`
-        // - `type::`
-        // - `vector::`
         // - ``
         _ => anyhow::bail!("Unsupported normal function: {}", name),
     })
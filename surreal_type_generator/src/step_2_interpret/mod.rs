@@ -1,8 +1,14 @@
+mod block;
+mod condition;
+mod data;
 mod function;
+mod narrowing;
 mod object;
+mod operator;
 mod return_types;
 mod schema;
 mod statements;
+mod transaction;
 mod utils;
 
 use crate::Kind;
@@ -11,70 +17,58 @@ use return_types::get_value_return_type;
 use statements::*;
 use std::collections::BTreeMap;
 use surrealdb::sql::{Statement, Subquery};
+use transaction::get_transaction_results;
 
 pub use schema::interpret_schema;
 pub use schema::QueryState;
 pub use schema::SchemaState;
 
+/// Interprets the statements of a query, returning the result type of each statement
+/// that produces a result, alongside the index of the statement that produced it.
 pub fn interpret_query(
-    statements: &Vec<Statement>,
+    statements: &[Statement],
     state: &mut QueryState,
-) -> Result<Vec<Kind>, anyhow::Error> {
+) -> Result<Vec<(usize, Kind)>, anyhow::Error> {
     let mut results = Vec::new();
-    let mut remaining_statements = statements.clone();
+    let mut remaining_statements = statements.iter().cloned().enumerate().collect::<Vec<_>>();
     // More efficient to pop from the end
     remaining_statements.reverse();
 
-    while let Some(stmt) = remaining_statements.pop() {
+    while let Some((index, stmt)) = remaining_statements.pop() {
         match stmt {
             Statement::Begin(_) => {
-                while let Some(stmt) = remaining_statements.pop() {
-                    let mut maybe_returns = Vec::new();
+                let mut transaction = Vec::new();
 
-                    match stmt {
-                        Statement::Commit(_) => {
-                            // We've hit a `COMMIT` statement, so we want to return the results
-                            // as we didn't run into any `RETURN` statements
-                            results.extend(maybe_returns);
+                loop {
+                    match remaining_statements.pop() {
+                        Some((_, Statement::Commit(_))) => {
+                            results.extend(get_transaction_results(transaction, state)?);
                             break;
                         }
-                        Statement::Begin(_) => {
-                            anyhow::bail!("Unexpected `BEGIN` statement in transaction block")
-                        }
-                        // We ran into a `RETURN` statement, so we want to just return the result
-                        stmt @ Statement::Output(_) => {
-                            // We ignore whatever came before since we are returning in this block
-                            match get_statement_return_type(&stmt, state)? {
-                                Some(kind) => results.push(kind),
-                                None => {}
-                            };
-
-                            // We want to ignore any statements after the `RETURN` statement
-                            // until we hit a `COMMIT` statement
-                            while let Some(stmt) = remaining_statements.pop() {
-                                if matches!(stmt, Statement::Commit(_)) {
-                                    break;
-                                }
-                            }
-                            // Break out of the BEGIN block
+                        // A cancelled transaction returns an error for every statement, so it has no results
+                        Some((_, Statement::Cancel(_))) => {
+                            get_transaction_results(transaction, state)?;
                             break;
                         }
-                        // We may return these values except if we hit a `RETURN` statement
-                        _ => match get_statement_return_type(&stmt, state)? {
-                            Some(kind) => maybe_returns.push(kind),
-                            None => {}
-                        },
+                        Some((_, Statement::Begin(_))) => {
+                            anyhow::bail!("Unexpected `BEGIN` statement in transaction block")
+                        }
+                        Some(stmt) => transaction.push(stmt),
+                        None => anyhow::bail!("Missing `COMMIT` statement in transaction block"),
                     }
                 }
-                continue;
             }
             Statement::Commit(_) => {
                 anyhow::bail!("Unexpected `COMMIT` statement in transaction block")
             }
-            stmt => match get_statement_return_type(&stmt, state)? {
-                Some(kind) => results.push(kind),
-                None => {}
-            },
+            Statement::Cancel(_) => {
+                anyhow::bail!("Unexpected `CANCEL` statement outside of a transaction block")
+            }
+            stmt => {
+                if let Some(kind) = get_statement_return_type(&stmt, state)? {
+                    results.push((index, kind));
+                }
+            }
         }
     }
 
@@ -96,6 +90,16 @@ fn get_statement_return_type(
         Statement::Relate(relate) => get_relate_statement_return_type(relate, state)?,
         Statement::Value(value) => get_value_return_type(value, &BTreeMap::new(), state)?,
         Statement::Set(set) => interpret_let_statement(set, state)?,
+        Statement::Ifelse(ifelse) => get_ifelse_statement_flow(ifelse, state)?.into_kind(),
+        Statement::Foreach(foreach) => get_foreach_statement_flow(foreach, state)?.into_kind(),
+        Statement::Throw(throw) => {
+            get_value_return_type(&throw.error, &BTreeMap::new(), state)?;
+            // the statement always errors, so it never has a result
+            Kind::Null
+        }
+        Statement::Break(_) | Statement::Continue(_) => {
+            anyhow::bail!("`{}` can only be used inside a `FOR` loop", stmt)
+        }
 
         _ => anyhow::bail!("Unsupported statement type: `{}`", stmt),
     }))
@@ -113,6 +117,7 @@ fn get_subquery_return_type(
         Subquery::Update(update) => get_update_statement_return_type(update, state),
         Subquery::Upsert(upsert) => get_upsert_statement_return_type(upsert, state),
         Subquery::Value(value) => get_value_return_type(value, &BTreeMap::new(), state),
+        Subquery::Ifelse(ifelse) => Ok(get_ifelse_statement_flow(ifelse, state)?.into_kind()),
         _ => anyhow::bail!("Unsupported subquery type: `{}`", subquery),
     }
 }
@@ -0,0 +1,360 @@
+use std::collections::BTreeMap;
+
+use surrealdb::sql::{Expression, Function, Literal, Operator, Part, Subquery, Value};
+
+use crate::Kind;
+
+use super::{
+    schema::QueryState,
+    utils::{split_optional_kind, union_kinds},
+};
+
+/// The narrowed kinds of parameters when a condition is truthy (`then`) or falsy (`otherwise`),
+/// eg: `IF $user != NONE` narrows `$user` from `option<record<user>>` to `record<user>` in the `then` branch.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Narrowing {
+    pub then: BTreeMap<String, Kind>,
+    pub otherwise: BTreeMap<String, Kind>,
+}
+
+impl Narrowing {
+    fn swap(self) -> Self {
+        Narrowing {
+            then: self.otherwise,
+            otherwise: self.then,
+        }
+    }
+}
+
+/// Returns the narrowed kinds of the parameters used in a condition, supporting:
+/// - truthiness, eg: `IF $user` or `IF !$user`
+/// - `NONE` and `NULL` checks, eg: `IF $user != NONE` or `IF $user IS NONE`
+/// - type checks, eg: `IF type::is::string($value)`
+/// - discriminated unions, eg: `IF $event.kind = "click"`
+/// - combinations of the above with `&&` and `||`
+///
+/// `narrowed` are the parameters which have already been narrowed, eg: by the previous `IF` conditions.
+pub fn get_condition_narrowing(
+    condition: &Value,
+    narrowed: &BTreeMap<String, Kind>,
+    state: &QueryState,
+) -> Narrowing {
+    let param_kind = |name: &str| narrowed.get(name).cloned().or_else(|| state.get(name));
+
+    match condition {
+        Value::Param(param) => match param_kind(param.as_str()) {
+            // `NONE` and `NULL` are both falsy
+            Some(kind) => Narrowing {
+                then: narrow_to_present(param.as_str(), &kind, true, true),
+                otherwise: BTreeMap::new(),
+            },
+            None => Narrowing::default(),
+        },
+        Value::Subquery(box Subquery::Value(value)) => {
+            get_condition_narrowing(value, narrowed, state)
+        }
+        Value::Expression(box Expression::Unary {
+            o: Operator::Not,
+            v,
+        }) => get_condition_narrowing(v, narrowed, state).swap(),
+        Value::Expression(box Expression::Binary {
+            l,
+            o: Operator::And,
+            r,
+        }) => {
+            let left = get_condition_narrowing(l, narrowed, state);
+            let mut then = left.then.clone();
+            then.extend(get_condition_narrowing(r, &overlay(narrowed, &left.then), state).then);
+
+            Narrowing {
+                then,
+                otherwise: BTreeMap::new(),
+            }
+        }
+        Value::Expression(box Expression::Binary {
+            l,
+            o: Operator::Or,
+            r,
+        }) => {
+            let left = get_condition_narrowing(l, narrowed, state);
+            let mut otherwise = left.otherwise.clone();
+            otherwise.extend(
+                get_condition_narrowing(r, &overlay(narrowed, &left.otherwise), state).otherwise,
+            );
+
+            Narrowing {
+                then: BTreeMap::new(),
+                otherwise,
+            }
+        }
+        Value::Expression(box Expression::Binary {
+            l,
+            o: o @ (Operator::Equal | Operator::Exact | Operator::NotEqual),
+            r,
+        }) => {
+            let narrowing = match (l, r) {
+                (Value::Param(param), absent @ (Value::None | Value::Null))
+                | (absent @ (Value::None | Value::Null), Value::Param(param)) => {
+                    match param_kind(param.as_str()) {
+                        Some(kind) => Narrowing {
+                            then: BTreeMap::new(),
+                            otherwise: narrow_to_present(
+                                param.as_str(),
+                                &kind,
+                                matches!(absent, Value::None),
+                                matches!(absent, Value::Null),
+                            ),
+                        },
+                        None => Narrowing::default(),
+                    }
+                }
+                (Value::Idiom(idiom), Value::Strand(value))
+                | (Value::Strand(value), Value::Idiom(idiom)) => match idiom.0.as_slice() {
+                    [Part::Start(Value::Param(param)), Part::Field(field)] => {
+                        match param_kind(param.as_str()) {
+                            Some(kind) => {
+                                narrow_discriminant(param.as_str(), &kind, field, value.as_str())
+                            }
+                            None => Narrowing::default(),
+                        }
+                    }
+                    _ => Narrowing::default(),
+                },
+                _ => Narrowing::default(),
+            };
+
+            match o {
+                Operator::NotEqual => narrowing.swap(),
+                _ => narrowing,
+            }
+        }
+        Value::Function(box Function::Normal(name, args)) => match (name.as_str(), args.as_slice())
+        {
+            (name, [Value::Param(param)]) if name.starts_with("type::is::") => {
+                match param_kind(param.as_str()) {
+                    Some(kind) => narrow_type_check(
+                        param.as_str(),
+                        &kind,
+                        name.trim_start_matches("type::is::"),
+                    ),
+                    None => Narrowing::default(),
+                }
+            }
+            _ => Narrowing::default(),
+        },
+        _ => Narrowing::default(),
+    }
+}
+
+/// Returns `narrowed` with the kinds in `overrides` replacing any existing kinds
+pub fn overlay(
+    narrowed: &BTreeMap<String, Kind>,
+    overrides: &BTreeMap<String, Kind>,
+) -> BTreeMap<String, Kind> {
+    let mut narrowed = narrowed.clone();
+    narrowed.extend(overrides.clone());
+    narrowed
+}
+
+/// Narrows a parameter to the values which aren't `NONE` and/or `NULL`, which SurrealDB treats as different values,
+/// eg: `$name != NONE` narrows `option<string | null>` to `string | null`, and `IF $name` narrows it to `string`.
+fn narrow_to_present(name: &str, kind: &Kind, none: bool, null: bool) -> BTreeMap<String, Kind> {
+    match without_absent(kind, none, null) {
+        Some(present) if present != *kind => BTreeMap::from([(name.to_string(), present)]),
+        _ => BTreeMap::new(),
+    }
+}
+
+/// Removes the `Option` layer when `none` is set, and any `null` members when `null` is set,
+/// or returns `None` when nothing is left.
+fn without_absent(kind: &Kind, none: bool, null: bool) -> Option<Kind> {
+    match kind {
+        Kind::Option(inner) if none => without_absent(inner, none, null),
+        Kind::Option(inner) => {
+            without_absent(inner, none, null).map(|inner| Kind::Option(Box::new(inner)))
+        }
+        Kind::Null if null => None,
+        Kind::Either(kinds) => {
+            let present = kinds
+                .iter()
+                .filter_map(|kind| without_absent(kind, none, null))
+                .collect::<Vec<_>>();
+
+            match present.is_empty() {
+                true => None,
+                false => Some(union_kinds(present)),
+            }
+        }
+        kind => Some(kind.clone()),
+    }
+}
+
+/// Narrows a union of objects by the literal value of one of their fields, eg: `$event.kind = "click"`
+fn narrow_discriminant(name: &str, kind: &Kind, field: &str, value: &str) -> Narrowing {
+    let (members, _) = union_members(kind);
+
+    let mut then = Vec::new();
+    let mut otherwise = Vec::new();
+
+    for member in members {
+        match &member {
+            Kind::Literal(Literal::Object(fields)) => match fields.get(field) {
+                Some(Kind::Literal(Literal::String(literal))) => match literal.as_str() == value {
+                    true => then.push(member),
+                    false => otherwise.push(member),
+                },
+                Some(Kind::Either(kinds))
+                    if kinds
+                        .iter()
+                        .all(|kind| matches!(kind, Kind::Literal(Literal::String(_)))) =>
+                {
+                    match kinds.iter().any(|kind| {
+                        matches!(kind, Kind::Literal(Literal::String(literal)) if literal.as_str() == value)
+                    }) {
+                        true => {
+                            then.push(member.clone());
+                            otherwise.push(member);
+                        }
+                        false => otherwise.push(member),
+                    }
+                }
+                // the field can't be equal to the value
+                None => otherwise.push(member),
+                Some(_) => {
+                    then.push(member.clone());
+                    otherwise.push(member);
+                }
+            },
+            // not an object, so the field is always `NONE`
+            _ => otherwise.push(member),
+        }
+    }
+
+    Narrowing {
+        then: narrowed_members(name, then, false),
+        // the param could also be `NONE` when the condition is false
+        otherwise: narrowed_members(name, otherwise, split_optional_kind(kind).1),
+    }
+}
+
+/// Narrows a union by a `type::is::*` check, eg: `type::is::string($value)`
+fn narrow_type_check(name: &str, kind: &Kind, check: &str) -> Narrowing {
+    let (members, optional) = union_members(kind);
+
+    match check {
+        "none" | "null" => {
+            return Narrowing {
+                then: BTreeMap::new(),
+                otherwise: narrow_to_present(name, kind, check == "none", check == "null"),
+            }
+        }
+        _ => {}
+    }
+
+    let mut then = Vec::new();
+    let mut otherwise = Vec::new();
+
+    for member in members {
+        match (&member, type_check_kind(check)) {
+            (Kind::Any, Some(kind)) => {
+                then.push(kind);
+                otherwise.push(member);
+            }
+            (member, _) if matches_type_check(member, check) => then.push(member.clone()),
+            (member, _) => otherwise.push(member.clone()),
+        }
+    }
+
+    Narrowing {
+        then: narrowed_members(name, then, false),
+        otherwise: narrowed_members(name, otherwise, optional),
+    }
+}
+
+fn union_members(kind: &Kind) -> (Vec<Kind>, bool) {
+    let (members, optional) = match split_optional_kind(kind) {
+        (Some(Kind::Either(kinds)), optional) => (kinds, optional),
+        (Some(kind), optional) => (vec![kind], optional),
+        (None, optional) => (Vec::new(), optional),
+    };
+
+    let members = members
+        .into_iter()
+        .flat_map(|member| match member {
+            // eg: `{ kind: "a", foo: string } | { kind: "b", bar: int }`
+            Kind::Literal(Literal::DiscriminatedObject(_, variants)) => variants
+                .into_iter()
+                .map(|fields| Kind::Literal(Literal::Object(fields)))
+                .collect(),
+            member => vec![member],
+        })
+        .collect();
+
+    (members, optional)
+}
+
+fn narrowed_members(name: &str, members: Vec<Kind>, optional: bool) -> BTreeMap<String, Kind> {
+    // a condition which is never true leaves the branch unnarrowed
+    if members.is_empty() {
+        return BTreeMap::new();
+    }
+
+    let kind = union_kinds(members);
+
+    BTreeMap::from([(
+        name.to_string(),
+        match optional {
+            true => Kind::Option(Box::new(kind)),
+            false => kind,
+        },
+    )])
+}
+
+fn type_check_kind(check: &str) -> Option<Kind> {
+    Some(match check {
+        "array" => Kind::Array(Box::new(Kind::Any), None),
+        "bool" => Kind::Bool,
+        "bytes" => Kind::Bytes,
+        "datetime" => Kind::Datetime,
+        "decimal" => Kind::Decimal,
+        "duration" => Kind::Duration,
+        "float" => Kind::Float,
+        "geometry" => Kind::Geometry(vec![]),
+        "int" => Kind::Int,
+        "number" => Kind::Number,
+        "object" => Kind::Object,
+        "point" => Kind::Point,
+        "record" => Kind::Record(vec![]),
+        "string" => Kind::String,
+        "uuid" => Kind::Uuid,
+        _ => return None,
+    })
+}
+
+fn matches_type_check(kind: &Kind, check: &str) -> bool {
+    match check {
+        "array" => matches!(kind, Kind::Array(..) | Kind::Literal(Literal::Array(_))),
+        "bool" => matches!(kind, Kind::Bool),
+        "bytes" => matches!(kind, Kind::Bytes),
+        "datetime" => matches!(kind, Kind::Datetime),
+        "decimal" => matches!(kind, Kind::Decimal),
+        "duration" => matches!(kind, Kind::Duration | Kind::Literal(Literal::Duration(_))),
+        "float" => matches!(kind, Kind::Float),
+        "geometry" => matches!(kind, Kind::Geometry(_) | Kind::Point),
+        "int" => matches!(kind, Kind::Int),
+        "number" => matches!(
+            kind,
+            Kind::Int
+                | Kind::Float
+                | Kind::Decimal
+                | Kind::Number
+                | Kind::Literal(Literal::Number(_))
+        ),
+        "object" => matches!(kind, Kind::Object | Kind::Literal(Literal::Object(_))),
+        "point" => matches!(kind, Kind::Point),
+        "record" => matches!(kind, Kind::Record(_)),
+        "string" => matches!(kind, Kind::String | Kind::Literal(Literal::String(_))),
+        "uuid" => matches!(kind, Kind::Uuid),
+        _ => false,
+    }
+}
@@ -15,5 +15,5 @@ pub fn get_object_return_type(state: &mut QueryState, obj: &Object) -> Result<Ki
         fields.insert(key.clone(), return_type);
     }
 
-    return Ok(kind!(Obj fields));
+    Ok(kind!(Obj fields))
 }
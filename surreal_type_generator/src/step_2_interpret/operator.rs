@@ -0,0 +1,116 @@
+use surrealdb::sql::{Literal, Number, Operator};
+
+use crate::Kind;
+
+use super::utils::union_kinds;
+
+/// Returns the type of an arithmetic expression (`+`, `-`, `*`, `/`, `**`, `%`),
+/// following SurrealDB's `try_add`, `try_sub`, etc.
+///
+/// Unions are distributed over their members, and optional operands are unwrapped,
+/// as SurrealDB throws rather than returning `NONE` when an operand is missing.
+pub fn get_arithmetic_return_type(
+    operator: &Operator,
+    l: &Kind,
+    r: &Kind,
+) -> Result<Kind, anyhow::Error> {
+    let left_kinds = operand_kinds(l);
+    let right_kinds = operand_kinds(r);
+
+    let mut results = Vec::new();
+    for left in &left_kinds {
+        for right in &right_kinds {
+            if let Some(kind) = arithmetic_kind(operator, left, right) {
+                results.push(kind);
+            }
+        }
+    }
+
+    if results.is_empty() {
+        match (operator, l, r) {
+            (Operator::Add, Kind::Array(..), Kind::Array(..)) => anyhow::bail!(
+                "Arrays cannot be added with `+`, use `array::concat` instead: `{}` + `{}`",
+                l,
+                r
+            ),
+            _ => anyhow::bail!("Cannot apply `{}` to `{}` and `{}`", operator, l, r),
+        }
+    }
+
+    Ok(union_kinds(results))
+}
+
+/// Returns the type of a negation expression, eg: `-$amount`
+pub fn get_negation_return_type(kind: &Kind) -> Result<Kind, anyhow::Error> {
+    let mut results = Vec::new();
+
+    for kind in operand_kinds(kind) {
+        match kind {
+            Kind::Any => results.push(Kind::Number),
+            kind if is_numeric(&kind) => results.push(kind),
+            _ => {}
+        }
+    }
+
+    if results.is_empty() {
+        anyhow::bail!("Cannot negate `{}`", kind);
+    }
+
+    Ok(union_kinds(results))
+}
+
+fn arithmetic_kind(operator: &Operator, l: &Kind, r: &Kind) -> Option<Kind> {
+    Some(match (operator, l, r) {
+        (Operator::Add | Operator::Sub, Kind::Any, _)
+        | (Operator::Add | Operator::Sub, _, Kind::Any) => Kind::Any,
+        (_, Kind::Any, _) | (_, _, Kind::Any) => Kind::Number,
+
+        // numbers
+        (Operator::Pow, l, r) if is_numeric(l) && is_numeric(r) => match (l, r) {
+            (Kind::Number, _) | (_, Kind::Number) => Kind::Number,
+            (Kind::Int, Kind::Int) => Kind::Int,
+            (Kind::Decimal, _) => Kind::Decimal,
+            _ => Kind::Float,
+        },
+        // dividing by zero returns `NaN`, rather than an int
+        (Operator::Div, Kind::Int, Kind::Int) => Kind::Number,
+        (_, l, r) if is_numeric(l) && is_numeric(r) => match (l, r) {
+            (Kind::Number, _) | (_, Kind::Number) => Kind::Number,
+            (Kind::Int, Kind::Int) => Kind::Int,
+            (Kind::Int | Kind::Float, Kind::Int | Kind::Float) => Kind::Float,
+            _ => Kind::Decimal,
+        },
+
+        // strings
+        (Operator::Add, Kind::String, Kind::String) => Kind::String,
+
+        // datetimes and durations
+        (Operator::Add | Operator::Sub, Kind::Datetime, Kind::Duration)
+        | (Operator::Add | Operator::Sub, Kind::Duration, Kind::Datetime) => Kind::Datetime,
+        (Operator::Sub, Kind::Datetime, Kind::Datetime) => Kind::Duration,
+        (Operator::Add | Operator::Sub, Kind::Duration, Kind::Duration) => Kind::Duration,
+
+        _ => return None,
+    })
+}
+
+/// Returns the possible kinds of an operand, unwrapping options and unions,
+/// and widening literals to their base kind.
+fn operand_kinds(kind: &Kind) -> Vec<Kind> {
+    match kind {
+        Kind::Option(inner) => operand_kinds(inner),
+        Kind::Either(kinds) => kinds.iter().flat_map(operand_kinds).collect(),
+        Kind::Null => Vec::new(),
+        Kind::Literal(Literal::String(_)) => vec![Kind::String],
+        Kind::Literal(Literal::Number(Number::Int(_))) => vec![Kind::Int],
+        Kind::Literal(Literal::Number(Number::Float(_))) => vec![Kind::Float],
+        Kind::Literal(Literal::Number(Number::Decimal(_))) => vec![Kind::Decimal],
+        Kind::Literal(Literal::Number(_)) => vec![Kind::Number],
+        Kind::Literal(Literal::Duration(_)) => vec![Kind::Duration],
+        kind => vec![kind.clone()],
+    }
+}
+
+fn is_numeric(kind: &Kind) -> bool {
+    matches!(kind, Kind::Int | Kind::Float | Kind::Decimal | Kind::Number)
+}
@@ -1,18 +1,20 @@
 use std::collections::{BTreeMap, HashSet};
 
 use surrealdb::sql::{
-    Cast, Constant, Dir, Expression, Field, Fields, Ident, Idiom, Literal, Operator, Param, Part,
-    Value,
+    Cast, Constant, Dir, Expression, Field, Fields, Geometry, Ident, Idiom, Literal, Operator,
+    Param, Part, Subquery, Value,
 };
 
 use crate::{kind, Kind};
 
 use super::{
+    block::get_block_flow,
     function::get_function_return_type,
     get_subquery_return_type,
     object::get_object_return_type,
+    operator::{get_arithmetic_return_type, get_negation_return_type},
     schema::QueryState,
-    utils::{get_what_fields, merge_into_map_recursively},
+    utils::{get_what_fields, merge_into_map_recursively, split_optional_kind, union_kinds},
 };
 
 pub fn get_statement_fields<F>(
@@ -22,7 +24,7 @@ pub fn get_statement_fields<F>(
     get_field_and_variables: F,
 ) -> Result<Kind, anyhow::Error>
 where
-    F: Fn(&mut BTreeMap<String, Kind>, &mut QueryState) -> (),
+    F: Fn(&mut BTreeMap<String, Kind>, &mut QueryState),
 {
     let mut return_types = Vec::new();
     let mut used_tables = HashSet::new();
@@ -30,12 +32,11 @@ where
     for table in what.iter() {
         let mut table_fields = get_what_fields(table, state)?;
 
-        if used_tables.contains(&table) {
+        // the same table can be selected from more than once, eg: `SELECT * FROM user, user`
+        if !used_tables.insert(table.to_string()) {
             continue;
         }
 
-        used_tables.insert(table);
-
         state.push_stack_frame();
 
         let return_type = if let Some(fields) = fields {
@@ -66,7 +67,7 @@ pub fn get_fields_return_values(
         // returning a single value with `VALUE`
         Fields {
             0: fields, 1: true, ..
-        } => Ok(get_field_return_type(&fields[0], &field_types, state)?
+        } => Ok(get_field_return_type(&fields[0], field_types, state)?
             .pop()
             .unwrap()
             .1),
@@ -79,12 +80,12 @@ pub fn get_fields_return_values(
             let mut map = BTreeMap::new();
 
             for field in fields {
-                for (idiom, return_type) in get_field_return_type(field, &field_types, state)? {
+                for (idiom, return_type) in get_field_return_type(field, field_types, state)? {
                     merge_into_map_recursively(&mut map, &idiom.0, return_type)?;
                 }
             }
 
-            return Ok(kind!(Obj map));
+            Ok(kind!(Obj map))
         }
     }
 }
@@ -127,7 +128,19 @@ pub fn get_value_return_type(
     state: &mut QueryState,
 ) -> Result<Kind, anyhow::Error> {
     Ok(match expr {
-        Value::Idiom(idiom) => get_field_from_paths(&idiom.0, &field_types, state)?,
+        Value::Idiom(idiom) => get_field_from_paths(&idiom.0, field_types, state)?,
+        // parenthesised expressions, eg: `(foo ?? bar)`, can access both the current document and `$parent`
+        Value::Subquery(box Subquery::Value(value)) => {
+            state.push_stack_frame();
+
+            state.set_local("parent", kind!(Obj field_types.clone()));
+
+            let return_type = get_value_return_type(value, field_types, state)?;
+
+            state.pop_stack_frame();
+
+            return_type
+        }
         Value::Subquery(subquery) => {
             state.push_stack_frame();
 
@@ -140,6 +153,7 @@ pub fn get_value_return_type(
             return_type
         }
         Value::Param(param) => get_parameter_return_type(param, state)?,
+        Value::Block(block) => get_block_flow(block, state)?.into_kind(),
         // TODO: These constants could potentially be represented as actual constants in the return types
         Value::Strand(_) => Kind::String,
         Value::Number(_) => Kind::Number,
@@ -148,7 +162,8 @@ pub fn get_value_return_type(
         Value::Datetime(_) => Kind::Datetime,
         Value::Duration(_) => Kind::Duration,
         Value::None => Kind::Null,
-        Value::Function(func) => get_function_return_type(state, &func)?,
+        Value::Thing(thing) => Kind::Record(vec![thing.tb.as_str().into()]),
+        Value::Function(func) => get_function_return_type(state, func, field_types)?,
         Value::Expression(expr) => get_expression_return_type(expr, field_types, state)?,
         Value::Array(array) => {
             let mut return_types = HashSet::new();
@@ -163,6 +178,16 @@ pub fn get_value_return_type(
             })
         }
         Value::Object(obj) => get_object_return_type(state, obj)?,
+        Value::Geometry(geometry) => Kind::Geometry(vec![match geometry {
+            Geometry::Point(_) => "point".into(),
+            Geometry::Line(_) => "line".into(),
+            Geometry::Polygon(_) => "polygon".into(),
+            Geometry::MultiPoint(_) => "multipoint".into(),
+            Geometry::MultiLine(_) => "multiline".into(),
+            Geometry::MultiPolygon(_) => "multipolygon".into(),
+            Geometry::Collection(_) => "collection".into(),
+            _ => anyhow::bail!("Unsupported geometry: {}", geometry),
+        }]),
         Value::Constant(constant) => match constant {
             Constant::MathE
             | Constant::MathFrac1Pi
@@ -203,8 +228,9 @@ pub fn get_expression_return_type(
             o: Operator::Not, ..
         } => Kind::Bool,
         Expression::Unary {
-            o: Operator::Neg, ..
-        } => anyhow::bail!("Unsupported unary operator"),
+            o: Operator::Neg,
+            v,
+        } => get_negation_return_type(&get_value_return_type(v, field_types, state)?)?,
 
         // logical binary expressions
         Expression::Binary {
@@ -212,91 +238,144 @@ pub fn get_expression_return_type(
         } => Kind::Bool,
         Expression::Binary {
             l,
-            o: Operator::Or,
+            o: Operator::Or | Operator::Tco,
             r,
         } => {
             let l = get_value_return_type(l, field_types, state)?;
             let r = get_value_return_type(r, field_types, state)?;
 
-            // If left is an `option<thing>` and right are both a `thing` we should return a `thing`
-            // This is because, if left is an option, it is considered falsey, and right will be returned
-            match (l, r) {
-                (Kind::Option(box left), right) => {
-                    if left == right {
-                        right
-                    } else {
-                        Kind::Either(vec![left, right])
-                    }
-                }
-                (left, right) => {
-                    if left == right {
-                        right
-                    } else {
-                        Kind::Either(vec![left, right])
-                    }
-                }
+            // If left is `NONE` or `NULL` it is falsey, and right will be returned.
+            // Any other value of left could also be falsey, eg: `0` or `""`, so right is always possible.
+            match split_optional_kind(&l) {
+                (Some(left), _) => union_kinds([left, r]),
+                (None, _) => r,
             }
         }
         Expression::Binary {
-            o: Operator::Equal, ..
-        } => Kind::Bool,
-        Expression::Binary {
-            o: Operator::NotEqual,
-            ..
-        } => Kind::Bool,
-        Expression::Binary {
-            o: Operator::Exact, ..
-        } => Kind::Bool,
+            l,
+            o: Operator::Nco,
+            r,
+        } => {
+            let l = get_value_return_type(l, field_types, state)?;
+            let r = get_value_return_type(r, field_types, state)?;
 
-        // comparison binary expressions
-        Expression::Binary {
-            o: Operator::LessThan,
-            ..
-        } => Kind::Bool,
-        Expression::Binary {
-            o: Operator::MoreThan,
-            ..
-        } => Kind::Bool,
+            // Right is only returned if left is `NONE` or `NULL`
+            match split_optional_kind(&l) {
+                (Some(left), true) => union_kinds([left, r]),
+                (Some(left), false) => left,
+                (None, _) => r,
+            }
+        }
+
+        // equality, comparison and fuzzy matching binary expressions
         Expression::Binary {
-            o: Operator::LessThanOrEqual,
+            o:
+                Operator::Equal
+                | Operator::Exact
+                | Operator::NotEqual
+                | Operator::AllEqual
+                | Operator::AnyEqual
+                | Operator::LessThan
+                | Operator::LessThanOrEqual
+                | Operator::MoreThan
+                | Operator::MoreThanOrEqual
+                | Operator::Like
+                | Operator::NotLike
+                | Operator::AllLike
+                | Operator::AnyLike,
             ..
         } => Kind::Bool,
+
+        // membership binary expressions, eg: `CONTAINS`, `CONTAINSANY`, `INSIDE`, `IN`, `NOT IN`
         Expression::Binary {
-            o: Operator::MoreThanOrEqual,
+            o:
+                Operator::Contain
+                | Operator::NotContain
+                | Operator::ContainAll
+                | Operator::ContainAny
+                | Operator::ContainNone
+                | Operator::Inside
+                | Operator::NotInside
+                | Operator::AllInside
+                | Operator::AnyInside
+                | Operator::NoneInside
+                | Operator::Outside
+                | Operator::Intersects,
             ..
         } => Kind::Bool,
+
+        // full-text search, eg: `body @@ $query` or `body @1@ $query`
         Expression::Binary {
-            o: Operator::Like, ..
-        } => Kind::Bool,
+            l,
+            o: Operator::Matches(..),
+            r,
+        } => {
+            get_value_return_type(l, field_types, state)?;
+            get_value_or_infer_parameter_return_type(r, Kind::String, field_types, state)?;
+            Kind::Bool
+        }
+
+        // vector search, eg: `embedding <|10|> $vector` or `embedding <|10,40|> $vector`
         Expression::Binary {
-            o: Operator::NotLike,
-            ..
-        } => Kind::Bool,
+            l,
+            o: Operator::Knn(..) | Operator::Ann(..),
+            r,
+        } => {
+            get_value_return_type(l, field_types, state)?;
+            get_value_or_infer_parameter_return_type(
+                r,
+                kind!(Arr Kind::Number),
+                field_types,
+                state,
+            )?;
+            Kind::Bool
+        }
 
-        // TODO: arithmetic
+        // arithmetic binary expressions
         Expression::Binary {
             l,
-            o: Operator::Add,
+            o:
+                o @ (Operator::Add
+                | Operator::Sub
+                | Operator::Mul
+                | Operator::Div
+                | Operator::Pow
+                | Operator::Rem),
             r,
         } => {
             let l = get_value_return_type(l, field_types, state)?;
             let r = get_value_return_type(r, field_types, state)?;
 
-            match (&l, &r) {
-                (Kind::Number, Kind::Number) => Kind::Number,
-                (Kind::String, Kind::String) => Kind::String,
-                (Kind::Datetime, Kind::Datetime) => Kind::Datetime,
-                (Kind::Duration, Kind::Duration) => Kind::Duration,
-                _ => anyhow::bail!("Unsupported binary operation: {:?}", expr),
-            }
+            get_arithmetic_return_type(o, &l, &r)?
         }
-        // Expression
-        // TODO: short circuiting
-        // TODO: more (contains, any, etc, outside, inside, fuzzy match)
+
+        // assignment operators are only valid in `SET` clauses
+        Expression::Binary {
+            o: o @ (Operator::Inc | Operator::Dec | Operator::Ext),
+            ..
+        } => anyhow::bail!("Operator `{}` is only valid in a `SET` clause", o),
+
         _ => anyhow::bail!("Unsupported expression: {}", expr),
     })
 }
 
+/// Returns the type of the value, or if the value is a parameter without a known type,
+/// infers the parameter as the expected `kind`, eg: `$vector` in `embedding <|10|> $vector`.
+pub fn get_value_or_infer_parameter_return_type(
+    value: &Value,
+    kind: Kind,
+    field_types: &BTreeMap<String, Kind>,
+    state: &mut QueryState,
+) -> Result<Kind, anyhow::Error> {
+    match value {
+        Value::Param(param) if state.get(param.as_str()).is_none() => {
+            state.infer(param.as_str(), kind.clone());
+            Ok(kind)
+        }
+        value => get_value_return_type(value, field_types, state),
+    }
+}
+
 pub fn get_parameter_return_type(
     param: &Param,
     state: &mut QueryState,
@@ -350,19 +429,19 @@ pub fn get_field_from_paths(
             }
         }
         Some(Part::Field(field_name)) => match field_types.get(field_name.as_str()) {
-            Some(return_type) => match_return_type(return_type, &parts[1..], field_types, state),
+            Some(return_type) => match_return_type(return_type, &parts[1..], state),
             None => anyhow::bail!("Field not found: {}", field_name),
         },
         Some(Part::Start(Value::Param(Param {
             0: Ident { 0: param_name, .. },
             ..
         }))) => match state.get(param_name) {
-            Some(return_type) => match_return_type(&return_type, &parts[1..], field_types, state),
+            Some(return_type) => match_return_type(&return_type, &parts[1..], state),
             None => anyhow::bail!("Unknown parameter: {}", param_name),
         },
         Some(Part::Start(Value::Subquery(subquery))) => {
             let return_type = get_subquery_return_type(subquery, state)?;
-            match_return_type(&return_type, &parts[1..], field_types, state)
+            match_return_type(&return_type, &parts[1..], state)
         }
         Some(Part::All) => Ok(kind!(Obj field_types.clone())),
         Some(_) => anyhow::bail!("Unsupported path: {}", Idiom::from(parts)),
@@ -370,14 +449,13 @@ pub fn get_field_from_paths(
     }
 }
 
+/// Returns the kind of the remaining `parts` of a path, applied to a value of the `return_type` kind,
+/// eg: `[Field(bar)]` applied to `{ bar: string }` for the path `foo.bar`.
 fn match_return_type(
     return_type: &Kind,
     parts: &[Part],
-    field_types: &BTreeMap<String, Kind>,
     state: &mut QueryState,
 ) -> Result<Kind, anyhow::Error> {
-    let has_next_part = parts.len() > 1;
-
     Ok(match return_type {
         Kind::String => Kind::String,
         Kind::Int => Kind::Int,
@@ -391,22 +469,23 @@ fn match_return_type(
         Kind::Any => Kind::Any,
         Kind::Number => Kind::Number,
         Kind::Object => Kind::Object,
+        Kind::Bytes => Kind::Bytes,
+        Kind::Point => Kind::Point,
+        Kind::Geometry(kinds) => Kind::Geometry(kinds.clone()),
         Kind::Literal(lit @ (Literal::String(_) | Literal::Number(_))) => {
             Kind::Literal(lit.clone())
         }
-        Kind::Literal(Literal::Object(nested_fields)) => {
-            if has_next_part {
-                get_field_from_paths(&parts[1..], nested_fields, state)?
-            } else {
-                kind!(Obj nested_fields.clone())
-            }
-        }
-        Kind::Record(tables) => {
-            if has_next_part {
+        Kind::Literal(Literal::Object(nested_fields)) => match parts.is_empty() {
+            true => kind!(Obj nested_fields.clone()),
+            false => get_field_from_paths(parts, nested_fields, state)?,
+        },
+        Kind::Record(tables) => match parts.is_empty() {
+            true => Kind::Record(tables.clone()),
+            false => {
                 let mut return_types = Vec::new();
                 for table in tables.iter() {
                     let return_type = get_field_from_paths(
-                        &parts[1..],
+                        parts,
                         &state.table_select_fields(table.as_str())?,
                         state,
                     )?;
@@ -417,47 +496,33 @@ fn match_return_type(
                 } else {
                     Kind::Either(return_types)
                 }
-            } else {
-                Kind::Record(tables.clone())
             }
-        }
-        Kind::Option(return_type) => kind!(Opt(match_return_type(
-            return_type,
-            &parts,
-            field_types,
-            state,
-        )?)),
-        Kind::Array(return_type, ..) => match parts.first() {
+        },
+        Kind::Option(return_type) => kind!(Opt(match_return_type(return_type, parts, state,)?)),
+        Kind::Array(return_type, size) => match parts.first() {
             Some(Part::Index(_)) => Kind::Option(Box::new(match_return_type(
                 return_type,
-                &parts,
-                field_types,
+                &parts[1..],
                 state,
             )?)),
             Some(Part::All) => kind!(Arr match_return_type(
-            return_type,
-            &parts,
-            field_types,
-            state,
-        )?),
+                return_type,
+                &parts[1..],
+                state,
+            )?),
+            // the field of every item in the array, eg: `foo.bar` where `foo` is `array<{ bar: string }>`
             Some(Part::Field(_)) => kind!(Arr match_return_type(
-            return_type,
-            &parts[1..],
-            field_types,
-            state,
-        )?),
+                return_type,
+                parts,
+                state,
+            )?),
             Some(_) => return Err(anyhow::anyhow!("Unsupported path: {}", Idiom::from(parts))),
-            None => {
-                return Err(anyhow::anyhow!(
-                    "Tried to access array with no fields: {}",
-                    Idiom::from(parts)
-                ))
-            }
+            None => Kind::Array(return_type.clone(), *size),
         },
         Kind::Either(return_types) => {
             let mut return_types = return_types.clone();
             for return_type in &mut return_types {
-                *return_type = match_return_type(&return_type, &parts, field_types, state)?;
+                *return_type = match_return_type(return_type, parts, state)?;
             }
             Kind::Either(return_types)
         }
@@ -1,28 +1,109 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
 
-use surrealdb::sql::{Block, Entry, Literal, Values};
+use surrealdb::sql::{Literal, Values};
 
 use crate::{
     step_1_parse_sql::{parse_schema, FunctionParsed, SchemaParsed, ViewParsed},
     Kind,
 };
 
-use super::{
-    get_create_statement_return_type, get_delete_statement_return_type,
-    get_insert_statement_return_type, get_return_statement_return_type,
-    get_select_statement_return_type, get_statement_fields, get_update_statement_return_type,
-};
+use super::{block::get_entries_flow, get_statement_fields, utils::is_kind_assignable};
 
 #[derive(Debug)]
 pub struct SchemaState {
     global_variables: BTreeMap<String, Kind>,
     pub schema: SchemaParsed,
+    /// Custom functions which have been interpreted, so each function body is only interpreted once
+    functions: Mutex<BTreeMap<String, CachedFunction>>,
+}
+
+#[derive(Debug, Clone)]
+enum CachedFunction {
+    /// The function body is currently being interpreted, so a call to it is recursive
+    Interpreting,
+    Interpreted(InterpretedFunction),
+}
+
+impl SchemaState {
+    /// Returns the interpreted custom function, interpreting its body the first time it is called.
+    ///
+    /// The body is interpreted in its own query state, as functions can't access the parameters of the query.
+    /// A declared return type, eg: `DEFINE FUNCTION fn::foo($n: int) -> int { ... }`, is used for recursive
+    /// calls while the body is interpreted, and the body must return a kind which can be assigned to it.
+    pub fn function(self: &Arc<Self>, name: &str) -> Result<InterpretedFunction, anyhow::Error> {
+        let func = match self.schema.functions.get(name) {
+            Some(func) => func,
+            None => anyhow::bail!("Unknown function: {}", name),
+        };
+
+        match (self.functions.lock().unwrap().get(name), &func.returns) {
+            (Some(CachedFunction::Interpreted(interpreted)), _) => return Ok(interpreted.clone()),
+            (Some(CachedFunction::Interpreting), Some(return_type)) => {
+                return Ok(InterpretedFunction {
+                    name: func.name.clone(),
+                    args: func.arguments.clone(),
+                    return_type: return_type.clone(),
+                })
+            }
+            (Some(CachedFunction::Interpreting), None) => anyhow::bail!(
+                "Recursive function `fn::{}` must declare its return type, eg: `DEFINE FUNCTION fn::{}(...) -> type {{ ... }}`",
+                name,
+                name
+            ),
+            (None, _) => {}
+        }
+
+        self.set_cached_function(name, Some(CachedFunction::Interpreting));
+
+        let mut state = QueryState::new(self.clone(), BTreeMap::new());
+        let interpreted = interpret_function_parsed(func.clone(), &mut state).and_then(
+            |interpreted| match &func.returns {
+                Some(return_type) if !is_kind_assignable(&interpreted.return_type, return_type) => {
+                    anyhow::bail!(
+                        "Function `fn::{}` declares that it returns `{}`, but its body returns `{}`",
+                        name,
+                        return_type,
+                        interpreted.return_type
+                    )
+                }
+                Some(return_type) => Ok(InterpretedFunction {
+                    return_type: return_type.clone(),
+                    ..interpreted
+                }),
+                None => Ok(interpreted),
+            },
+        );
+
+        // errors aren't cached, so they are reported again for each call
+        self.set_cached_function(
+            name,
+            interpreted
+                .as_ref()
+                .ok()
+                .map(|interpreted| CachedFunction::Interpreted(interpreted.clone())),
+        );
+
+        interpreted
+    }
+
+    fn set_cached_function(&self, name: &str, cached: Option<CachedFunction>) {
+        let mut functions = self.functions.lock().unwrap();
+        match cached {
+            Some(cached) => functions.insert(name.to_string(), cached),
+            None => functions.remove(name),
+        };
+    }
 }
 
 #[derive(Debug)]
 pub struct QueryState {
     pub schema: Arc<SchemaState>,
     pub in_transaction: bool,
+    /// Whether a `FOR` loop is being interpreted, where `BREAK` and `CONTINUE` are valid
+    pub in_loop: bool,
     defined_variables: BTreeMap<String, Kind>,
     inferred_variables: BTreeMap<String, Kind>,
     stack_variables: Vec<BTreeMap<String, Kind>>,
@@ -33,6 +114,7 @@ impl QueryState {
         Self {
             schema,
             in_transaction: false,
+            in_loop: false,
             defined_variables,
             inferred_variables: BTreeMap::new(),
             // initial global query stack frame for any LET statements
@@ -45,8 +127,8 @@ impl QueryState {
     }
 
     pub fn get(&self, key: &str) -> Option<Kind> {
-        let mut stack_variables = self.stack_variables.iter().rev();
-        while let Some(frame) = stack_variables.next() {
+        let stack_variables = self.stack_variables.iter().rev();
+        for frame in stack_variables {
             if let Some(value) = frame.get(key) {
                 return Some(value.clone());
             }
@@ -93,10 +175,7 @@ impl QueryState {
     }
 
     pub fn function(&mut self, name: &str) -> Result<InterpretedFunction, anyhow::Error> {
-        match self.schema.schema.functions.get(name).cloned() {
-            Some(func) => Ok(interpret_function_parsed(func, self)?),
-            None => anyhow::bail!("Unknown function: {}", name),
-        }
+        self.schema.function(name)
     }
 
     pub fn extract_required_variables(&self) -> BTreeMap<String, Kind> {
@@ -132,45 +211,30 @@ pub fn interpret_schema(
     Ok(SchemaState {
         global_variables,
         schema: parse_schema(schema)?,
+        functions: Mutex::new(BTreeMap::new()),
     })
 }
 
 fn interpret_function_parsed(
     func: FunctionParsed,
-    operation_state: &mut QueryState,
+    state: &mut QueryState,
 ) -> Result<InterpretedFunction, anyhow::Error> {
-    operation_state.push_stack_frame();
+    state.push_stack_frame();
 
     for (name, return_type) in func.arguments.iter() {
-        operation_state.set_local(&name, return_type.clone());
+        state.set_local(name, return_type.clone());
     }
 
-    let func = InterpretedFunction {
-        name: func.name,
-        args: func.arguments,
-        return_type: get_block_return_type(func.block, operation_state)?,
-    };
-
-    operation_state.pop_stack_frame();
+    // the function body is a block, so it evaluates to the union of any `RETURN`s and its final value
+    let flow = get_entries_flow(&func.block.0, state);
 
-    Ok(func)
-}
-
-fn get_block_return_type(block: Block, state: &mut QueryState) -> Result<Kind, anyhow::Error> {
-    for entry in block.0.into_iter() {
-        match entry {
-            Entry::Output(output) => return get_return_statement_return_type(&output, state),
-            Entry::Create(create) => return get_create_statement_return_type(&create, state),
-            Entry::Insert(insert) => return get_insert_statement_return_type(&insert, state),
-            Entry::Delete(delete) => return get_delete_statement_return_type(&delete, state),
-            Entry::Select(select) => return get_select_statement_return_type(&select, state),
-            Entry::Update(update) => return get_update_statement_return_type(&update, state),
-            // Entry::Upsert(upsert) => return get_upsert_statement_return_type(&upsert, state),
-            _ => anyhow::bail!("Entry type: {} has not been implemented", entry),
-        }
-    }
+    state.pop_stack_frame();
 
-    Ok(Kind::Null)
+    Ok(InterpretedFunction {
+        name: func.name,
+        args: func.arguments,
+        return_type: flow?.into_kind(),
+    })
 }
 
 fn get_view_table(
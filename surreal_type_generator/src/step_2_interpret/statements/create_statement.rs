@@ -2,7 +2,12 @@ use surrealdb::sql::{statements::CreateStatement, Data, Fields, Output, Value, V
 
 use crate::{
     kind,
-    step_2_interpret::{get_statement_fields, schema::QueryState, utils::get_value_table},
+    step_2_interpret::{
+        data::{interpret_object_data, interpret_set_expressions},
+        get_statement_fields,
+        schema::QueryState,
+        utils::get_value_table,
+    },
     Kind,
 };
 
@@ -23,9 +28,8 @@ pub fn get_create_statement_return_type(
         _ => anyhow::bail!("Unknown CREATE statement type: {}", create),
     };
 
-    match &create.data {
-        Some(content) => validate_data_type(state, &create.what, content)?,
-        None => {}
+    if let Some(content) = &create.data {
+        validate_data_type(state, &create.what, content)?
     }
 
     if is_only {
@@ -75,11 +79,15 @@ fn validate_data_type(
             if tables.len() == 1 {
                 state.infer(param.0.as_str(), tables.pop().unwrap());
             } else if tables.len() > 1 {
-                state.infer(&param.0.as_str(), Kind::Either(tables));
+                state.infer(param.0.as_str(), Kind::Either(tables));
             }
 
             Ok(())
         }
+        Data::ContentExpression(Value::Object(object)) => {
+            interpret_object_data(object, what, true, state)
+        }
+        Data::SetExpression(sets) => interpret_set_expressions(sets, what, true, state),
         // TODO: support other data types and variable inference
         _ => Ok(()),
     }
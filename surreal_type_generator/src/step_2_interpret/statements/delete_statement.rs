@@ -2,7 +2,9 @@ use surrealdb::sql::{statements::DeleteStatement, Fields, Kind, Output};
 
 use crate::{
     kind,
-    step_2_interpret::{get_statement_fields, schema::QueryState},
+    step_2_interpret::{
+        condition::interpret_where_condition, get_statement_fields, schema::QueryState,
+    },
 };
 
 pub fn get_delete_statement_return_type(
@@ -11,6 +13,10 @@ pub fn get_delete_statement_return_type(
 ) -> Result<Kind, anyhow::Error> {
     let is_only = delete.only;
 
+    if let Some(cond) = &delete.cond {
+        interpret_where_condition(cond, &delete.what, state)?;
+    }
+
     let return_type = match &delete.output {
         Some(Output::After) => Kind::Null,
         Some(Output::Before) => get_delete_fields(delete, state, None)?,
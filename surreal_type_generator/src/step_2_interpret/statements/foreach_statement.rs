@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+
+use surrealdb::sql::{statements::ForeachStatement, Literal, Value};
+
+use crate::{
+    step_2_interpret::{
+        block::{get_block_flow, BlockFlow},
+        return_types::get_value_return_type,
+        utils::union_kinds,
+        QueryState,
+    },
+    Kind,
+};
+
+/// Interprets the block of a `FOR` loop in its own stack frame, with the loop parameter typed as
+/// the item kind of the iterated array or range. The loop itself always evaluates to `NONE`,
+/// but any `RETURN` statements in the block exit the enclosing block.
+pub fn get_foreach_statement_flow(
+    foreach: &ForeachStatement,
+    state: &mut QueryState,
+) -> Result<BlockFlow, anyhow::Error> {
+    let item = match &foreach.range {
+        // eg: `FOR $i IN 0..10`
+        Value::Range(_) => Kind::Int,
+        range => get_item_kind(&get_value_return_type(range, &BTreeMap::new(), state)?)?,
+    };
+
+    state.push_stack_frame();
+    state.set_local(foreach.param.as_str(), item);
+
+    let in_loop = std::mem::replace(&mut state.in_loop, true);
+    let flow = get_block_flow(&foreach.block, state);
+    state.in_loop = in_loop;
+
+    state.pop_stack_frame();
+
+    Ok(BlockFlow {
+        value: Some(Kind::Null),
+        returns: flow?.returns,
+        narrowed: BTreeMap::new(),
+    })
+}
+
+fn get_item_kind(kind: &Kind) -> Result<Kind, anyhow::Error> {
+    Ok(match kind {
+        Kind::Array(item, ..) | Kind::Set(item, ..) => *item.clone(),
+        Kind::Literal(Literal::Array(items)) => union_kinds(items.clone()),
+        Kind::Range => Kind::Int,
+        Kind::Any => Kind::Any,
+        // iterating over `NONE` is a no-op, eg: `FOR $tag IN $tags` where `$tags` is `option<array<string>>`
+        Kind::Option(kind) => get_item_kind(kind)?,
+        Kind::Either(kinds) => {
+            let mut items = Vec::new();
+            for kind in kinds {
+                items.push(get_item_kind(kind)?);
+            }
+            union_kinds(items)
+        }
+        kind => anyhow::bail!(
+            "`FOR` can only iterate over an array or range, got: {}",
+            kind
+        ),
+    })
+}
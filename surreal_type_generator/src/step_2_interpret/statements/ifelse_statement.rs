@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+
+use surrealdb::sql::{statements::IfelseStatement, Value};
+
+use crate::{
+    step_2_interpret::{
+        block::{get_block_flow, BlockFlow},
+        narrowing::{get_condition_narrowing, overlay},
+        return_types::get_value_return_type,
+        utils::union_kinds,
+        QueryState,
+    },
+    Kind,
+};
+
+/// Interprets each branch of an `IF` statement in its own stack frame,
+/// with the parameters narrowed by the branch's condition, and the negation of the previous conditions.
+/// The value is the union of the branches, which is optional when there is no `ELSE` branch.
+pub fn get_ifelse_statement_flow(
+    ifelse: &IfelseStatement,
+    state: &mut QueryState,
+) -> Result<BlockFlow, anyhow::Error> {
+    let mut values = Vec::new();
+    let mut returns = Vec::new();
+    // the narrowed parameters when none of the previous conditions were true
+    let mut otherwise = BTreeMap::new();
+    // the narrowed parameters of each branch which runs to completion
+    let mut completed = Vec::new();
+
+    for (condition, then) in ifelse.exprs.iter() {
+        get_value_return_type(condition, &BTreeMap::new(), state)?;
+
+        let narrowing = get_condition_narrowing(condition, &otherwise, state);
+        let narrowed = overlay(&otherwise, &narrowing.then);
+
+        let flow = get_branch_flow(then, &narrowed, state)?;
+        if let Some(value) = flow.value {
+            values.push(value);
+            completed.push(narrowed);
+        }
+        returns.extend(flow.returns);
+
+        otherwise = overlay(&otherwise, &narrowing.otherwise);
+    }
+
+    let has_else = match &ifelse.close {
+        Some(close) => {
+            let flow = get_branch_flow(close, &otherwise, state)?;
+            if let Some(value) = flow.value {
+                values.push(value);
+                completed.push(otherwise);
+            }
+            returns.extend(flow.returns);
+            true
+        }
+        None => {
+            completed.push(otherwise);
+            false
+        }
+    };
+
+    let value = match (values.is_empty(), has_else) {
+        // every branch returned or threw early
+        (true, true) => None,
+        // only reached when no condition matches, and there is no `ELSE` branch
+        (true, false) => Some(Kind::Null),
+        (false, true) => Some(union_kinds(values)),
+        (false, false) => Some(match union_kinds(values) {
+            kind @ Kind::Option(..) => kind,
+            kind => Kind::Option(Box::new(kind)),
+        }),
+    };
+
+    Ok(BlockFlow {
+        value,
+        returns,
+        narrowed: get_continuation_narrowing(completed, state),
+    })
+}
+
+fn get_branch_flow(
+    branch: &Value,
+    narrowed: &BTreeMap<String, Kind>,
+    state: &mut QueryState,
+) -> Result<BlockFlow, anyhow::Error> {
+    state.push_stack_frame();
+
+    for (name, kind) in narrowed {
+        state.set_local(name, kind.clone());
+    }
+
+    let flow = match branch {
+        Value::Block(block) => get_block_flow(block, state),
+        value => get_value_return_type(value, &BTreeMap::new(), state).map(BlockFlow::value),
+    };
+
+    state.pop_stack_frame();
+
+    flow
+}
+
+/// Returns the narrowed parameters after the statement, which is the union of the narrowed kinds
+/// of every branch which runs to completion, eg: after `IF $user = NONE { RETURN NONE; }`
+/// only the implicit `ELSE` branch completes, where `$user` is not `NONE`.
+fn get_continuation_narrowing(
+    completed: Vec<BTreeMap<String, Kind>>,
+    state: &QueryState,
+) -> BTreeMap<String, Kind> {
+    let mut narrowed = BTreeMap::new();
+
+    let Some(first) = completed.first() else {
+        return narrowed;
+    };
+
+    for name in first.keys() {
+        let mut kinds = Vec::new();
+        for branch in completed.iter() {
+            match branch.get(name).cloned().or_else(|| state.get(name)) {
+                Some(kind) => kinds.push(kind),
+                None => return BTreeMap::new(),
+            }
+        }
+        narrowed.insert(name.clone(), union_kinds(kinds));
+    }
+
+    narrowed
+}
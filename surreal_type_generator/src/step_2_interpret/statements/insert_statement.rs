@@ -2,7 +2,10 @@ use surrealdb::sql::{statements::InsertStatement, Data, Fields, Output, Value};
 
 use crate::{
     kind,
-    step_2_interpret::{get_statement_fields, schema::QueryState, utils::get_value_table},
+    step_2_interpret::{
+        data::interpret_object_data, get_statement_fields, schema::QueryState,
+        utils::get_value_table,
+    },
     Kind,
 };
 
@@ -16,16 +19,16 @@ pub fn get_insert_statement_return_type(
     };
 
     let return_type = match &insert.output {
-        Some(Output::After) | None => get_insert_fields(&into, state, None)?,
+        Some(Output::After) | None => get_insert_fields(into, state, None)?,
         Some(Output::Before | Output::Null) => Kind::Null,
         Some(Output::None) => Kind::Null,
         Some(Output::Diff) => anyhow::bail!("Insert with returned diff is not currently supported"),
-        Some(Output::Fields(fields)) => get_insert_fields(&into, state, Some(fields))?,
+        Some(Output::Fields(fields)) => get_insert_fields(into, state, Some(fields))?,
         #[allow(unreachable_patterns)]
         _ => anyhow::bail!("Unknown INSERT statement type: {}", insert),
     };
 
-    validate_data_type(state, &into, &insert.data)?;
+    validate_data_type(state, into, &insert.data)?;
 
     Ok(kind!(Arr return_type))
 }
@@ -35,11 +38,16 @@ fn get_insert_fields(
     state: &mut QueryState,
     fields: Option<&Fields>,
 ) -> Result<Kind, anyhow::Error> {
-    get_statement_fields(&[table.clone()], state, fields, |fields, state| {
-        state.set_local("after", kind!(Obj fields.clone()));
-        state.set_local("before", kind!(Null));
-        state.set_local("this", kind!(Obj fields.clone()));
-    })
+    get_statement_fields(
+        std::slice::from_ref(table),
+        state,
+        fields,
+        |fields, state| {
+            state.set_local("after", kind!(Obj fields.clone()));
+            state.set_local("before", kind!(Null));
+            state.set_local("this", kind!(Obj fields.clone()));
+        },
+    )
 }
 
 fn validate_data_type(
@@ -52,7 +60,7 @@ fn validate_data_type(
             let mut tables = vec![];
 
             // for value in values {
-            let table_name = get_value_table(&table, state)?;
+            let table_name = get_value_table(table, state)?;
 
             match state.schema.schema.tables.get(&table_name) {
                 Some(table) => {
@@ -69,9 +77,21 @@ fn validate_data_type(
             // }
 
             if tables.len() == 1 {
-                state.infer(&param.0.as_str(), tables.pop().unwrap());
+                state.infer(param.0.as_str(), tables.pop().unwrap());
             } else if tables.len() > 1 {
-                state.infer(&param.0.as_str(), Kind::Either(tables));
+                state.infer(param.0.as_str(), Kind::Either(tables));
+            }
+
+            Ok(())
+        }
+        Data::SingleExpression(Value::Object(object)) => {
+            interpret_object_data(object, std::slice::from_ref(table), true, state)
+        }
+        Data::SingleExpression(Value::Array(records)) => {
+            for record in records.iter() {
+                if let Value::Object(object) = record {
+                    interpret_object_data(object, std::slice::from_ref(table), true, state)?
+                }
             }
 
             Ok(())
@@ -1,5 +1,7 @@
 mod create_statement;
 mod delete_statement;
+mod foreach_statement;
+mod ifelse_statement;
 mod insert_statement;
 mod let_statement;
 mod relate_statement;
@@ -10,6 +12,8 @@ mod upsert_statement;
 
 pub use create_statement::get_create_statement_return_type;
 pub use delete_statement::get_delete_statement_return_type;
+pub use foreach_statement::get_foreach_statement_flow;
+pub use ifelse_statement::get_ifelse_statement_flow;
 pub use insert_statement::get_insert_statement_return_type;
 pub use let_statement::interpret_let_statement;
 pub use relate_statement::get_relate_statement_return_type;
@@ -52,7 +52,7 @@ fn get_relate_fields(
     // If specific fields were requested, filter to just those
     if let Some(fields) = fields {
         let filtered_fields = get_statement_fields(
-            &[relate.kind.clone()],
+            std::slice::from_ref(&relate.kind),
             state,
             Some(fields),
             |fields, state| {
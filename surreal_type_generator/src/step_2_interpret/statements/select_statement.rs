@@ -1,6 +1,9 @@
 use crate::{
     kind,
-    step_2_interpret::{return_types::get_statement_fields, schema::QueryState},
+    step_2_interpret::{
+        condition::interpret_where_condition, return_types::get_statement_fields,
+        schema::QueryState,
+    },
     Kind,
 };
 
@@ -22,6 +25,10 @@ fn get_select_fields(
     select: &SelectStatement,
     state: &mut QueryState,
 ) -> Result<Kind, anyhow::Error> {
+    if let Some(cond) = &select.cond {
+        interpret_where_condition(cond, &select.what, state)?;
+    }
+
     get_statement_fields(&select.what, state, Some(&select.expr), |fields, state| {
         state.set_local("this", kind!(Obj fields.clone()));
     })
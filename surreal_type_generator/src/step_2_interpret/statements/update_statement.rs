@@ -1,8 +1,17 @@
-use surrealdb::sql::{statements::UpdateStatement, Data, Fields, Output, Values};
+use surrealdb::sql::{
+    statements::UpdateStatement, Data, Fields, Literal, Output, Param, Value, Values,
+};
 
 use crate::{
     kind,
-    step_2_interpret::{get_statement_fields, schema::QueryState},
+    step_1_parse_sql::TableParsed,
+    step_2_interpret::{
+        condition::interpret_where_condition,
+        data::{interpret_object_data, interpret_set_expressions},
+        get_statement_fields,
+        schema::QueryState,
+        utils::{get_value_table, union_kinds},
+    },
     Kind,
 };
 
@@ -28,9 +37,13 @@ pub fn get_update_statement_return_type(
         )))?,
     };
 
-    match &update.data {
-        Some(content) => validate_data_type(state, &update.what, &content)?,
-        None => {}
+    if let Some(content) = &update.data {
+        validate_data_type(state, &update.what, content)?
+    }
+
+    // parameters which are written to fields are inferred from the data first
+    if let Some(cond) = &update.cond {
+        interpret_where_condition(cond, &update.what, state)?;
     }
 
     if is_only {
@@ -60,33 +73,99 @@ fn validate_data_type(
     what: &Values,
     data: &Data,
 ) -> Result<(), anyhow::Error> {
-    let _ = state;
-    let _ = what;
     match data {
-        Data::SetExpression(sets) => {
-            for _set in sets.iter() {
-                // TODO
-
-                // let mut tables = vec![];
-
-                // for table in what.iter() {
-                //     let table_name = match &table {
-                //         Value::Table(table) => table.0.as_str(),
-                //         _ => anyhow::bail!("Expected table name"),
-                //     };
-                // }
-
-                // if tables.len() == 1 {
-                //     state.infer(param.0.as_str(), tables.pop().unwrap());
-                // } else if tables.len() > 1 {
-                //     state.infer(&param.0.as_str(), Kind::Either(tables));
-                // }
-            }
-
+        Data::SetExpression(sets) => interpret_set_expressions(sets, what, false, state),
+        // the record's content is replaced, so the whole record must be provided, except `READONLY` fields
+        Data::ContentExpression(Value::Param(param)) => {
+            infer_table_data_parameter(param, what, state, |table| {
+                Ok(kind!(Obj table.compute_content_fields()?))
+            })
+        }
+        // `READONLY` fields aren't kept, so they must be provided with their stored values
+        Data::ReplaceExpression(Value::Param(param)) => {
+            infer_table_data_parameter(param, what, state, |table| {
+                Ok(kind!(Obj table.compute_create_fields()?))
+            })
+        }
+        Data::MergeExpression(Value::Param(param)) => {
+            infer_table_data_parameter(param, what, state, |table| {
+                Ok(kind!(Obj table.compute_update_fields()?))
+            })
+        }
+        Data::PatchExpression(Value::Param(param)) => {
+            state.infer(param.as_str(), get_json_patch_kind());
             Ok(())
         }
+        Data::MergeExpression(Value::Object(object))
+        | Data::ContentExpression(Value::Object(object))
+        | Data::ReplaceExpression(Value::Object(object)) => {
+            interpret_object_data(object, what, false, state)
+        }
         _ => Err(anyhow::anyhow!(
             "Unsupported data type for UPDATE statement"
         ))?,
     }
 }
+
+fn infer_table_data_parameter(
+    param: &Param,
+    what: &Values,
+    state: &mut QueryState,
+    compute_kind: impl Fn(&TableParsed) -> Result<Kind, anyhow::Error>,
+) -> Result<(), anyhow::Error> {
+    let mut tables = Vec::new();
+
+    for table in what.iter() {
+        let table_name = get_value_table(table, state)?;
+        match state.schema.schema.tables.get(&table_name) {
+            Some(table) => tables.push(compute_kind(table)?),
+            None => anyhow::bail!(
+                "Trying to update a record with an unknown or view table: {}",
+                table_name
+            ),
+        }
+    }
+
+    if tables.len() == 1 {
+        state.infer(param.as_str(), tables.pop().unwrap());
+    } else if tables.len() > 1 {
+        state.infer(param.as_str(), Kind::Either(tables));
+    }
+
+    Ok(())
+}
+
+/// Returns the kind of a JSON Patch, which is an array of operations, eg:
+/// `[{ op: "replace", path: "/name", value: "John" }, { op: "remove", path: "/age" }]`
+fn get_json_patch_kind() -> Kind {
+    let op = |ops: &[&str]| {
+        union_kinds(
+            ops.iter()
+                .map(|op| Kind::Literal(Literal::String((*op).into())))
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    kind!(Arr Kind::Either(vec![
+        kind!({
+            op: op(&["add", "replace", "test"]),
+            path: kind!(String),
+            value: kind!(Any)
+        }),
+        kind!({
+            op: op(&["remove"]),
+            path: kind!(String)
+        }),
+        kind!({
+            op: op(&["copy", "move"]),
+            from: kind!(String),
+            path: kind!(String)
+        }),
+        // a diff-match-patch of a string field
+        kind!({
+            op: op(&["change"]),
+            path: kind!(String),
+            value: kind!(String)
+        }),
+    ]))
+}
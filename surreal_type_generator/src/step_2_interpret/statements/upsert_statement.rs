@@ -2,7 +2,13 @@ use surrealdb::sql::{statements::UpsertStatement, Data, Fields, Output, Value, V
 
 use crate::{
     kind,
-    step_2_interpret::{get_statement_fields, schema::QueryState, utils::get_value_table},
+    step_2_interpret::{
+        condition::interpret_where_condition,
+        data::{interpret_object_data, interpret_set_expressions},
+        get_statement_fields,
+        schema::QueryState,
+        utils::get_value_table,
+    },
     Kind,
 };
 
@@ -27,9 +33,13 @@ pub fn get_upsert_statement_return_type(
         )))?,
     };
 
-    match &upsert.data {
-        Some(content) => validate_data_type(state, &upsert.what, content)?,
-        None => {}
+    if let Some(content) = &upsert.data {
+        validate_data_type(state, &upsert.what, content)?
+    }
+
+    // parameters which are written to fields are inferred from the data first
+    if let Some(cond) = &upsert.cond {
+        interpret_where_condition(cond, &upsert.what, state)?;
     }
 
     if is_only {
@@ -68,11 +78,17 @@ pub fn validate_data_type(
             if tables.len() == 1 {
                 state.infer(param.0.as_str(), tables.pop().unwrap());
             } else if tables.len() > 1 {
-                state.infer(&param.0.as_str(), Kind::Either(tables));
+                state.infer(param.0.as_str(), Kind::Either(tables));
             }
 
             Ok(())
         }
+        // the record may already exist, so `READONLY` fields can't be set
+        Data::MergeExpression(Value::Object(object))
+        | Data::ContentExpression(Value::Object(object)) => {
+            interpret_object_data(object, what, false, state)
+        }
+        Data::SetExpression(sets) => interpret_set_expressions(sets, what, false, state),
         // TODO: support other data types and variable inference
         _ => Ok(()),
     }
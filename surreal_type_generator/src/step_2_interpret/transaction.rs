@@ -0,0 +1,123 @@
+use surrealdb::sql::Statement;
+
+use crate::{step_1_parse_sql::statements_to_block_ast, Kind};
+
+use super::{
+    block::get_entries_flow,
+    get_statement_return_type,
+    schema::QueryState,
+    statements::{get_foreach_statement_flow, get_ifelse_statement_flow},
+    utils::union_kinds,
+};
+
+/// Returns the results of the statements inside a `BEGIN ... COMMIT` transaction.
+///
+/// A `RETURN` directly inside the transaction replaces all of its results with the returned value,
+/// so the transaction is interpreted as a block with a single result.
+/// Otherwise a `RETURN` nested in an `IF` or `FOR` statement may exit early with a single result,
+/// or the transaction falls through with a result for each statement,
+/// and the two result tuples are unioned with [`union_result_tuples`].
+pub fn get_transaction_results(
+    statements: Vec<(usize, Statement)>,
+    state: &mut QueryState,
+) -> Result<Vec<(usize, Kind)>, anyhow::Error> {
+    if let Some((index, _)) = statements
+        .iter()
+        .find(|(_, stmt)| matches!(stmt, Statement::Output(_)))
+    {
+        let index = *index;
+        let block =
+            statements_to_block_ast(statements.into_iter().map(|(_, stmt)| stmt).collect())?;
+        let kind = get_entries_flow(&block.0, state)?.into_kind();
+
+        return Ok(vec![(index, kind)]);
+    }
+
+    let mut fallthrough = Some(Vec::new());
+    let mut returns = Vec::new();
+    // the index of the first statement which may exit early
+    let mut return_index = None;
+
+    for (index, stmt) in statements {
+        let flow = match &stmt {
+            Statement::Ifelse(ifelse) => get_ifelse_statement_flow(ifelse, state)?,
+            Statement::Foreach(foreach) => get_foreach_statement_flow(foreach, state)?,
+            stmt => {
+                if let (Some(kind), Some(results)) = (
+                    get_statement_return_type(stmt, state)?,
+                    fallthrough.as_mut(),
+                ) {
+                    results.push((index, kind));
+                }
+                continue;
+            }
+        };
+
+        if !flow.returns.is_empty() {
+            return_index.get_or_insert(index);
+            returns.extend(flow.returns);
+        }
+
+        // the rest of the transaction is skipped after a `RETURN`,
+        // so the narrowing of the branches which complete applies to the following statements
+        for (name, kind) in flow.narrowed {
+            state.set_local(&name, kind);
+        }
+
+        match flow.value {
+            Some(kind) => {
+                if let Some(results) = fallthrough.as_mut() {
+                    results.push((index, kind));
+                }
+            }
+            // every branch returned or threw, so the transaction never falls through
+            None => {
+                fallthrough = None;
+                break;
+            }
+        }
+    }
+
+    let mut alternatives = Vec::new();
+    alternatives.extend(fallthrough);
+    if let Some(index) = return_index {
+        alternatives.push(vec![(index, union_kinds(returns))]);
+    }
+
+    Ok(union_result_tuples(alternatives))
+}
+
+/// Unions the possible result tuples of a transaction into a single tuple,
+/// by unioning the kinds at each position, and making positions which are missing
+/// from some of the tuples optional, eg: `[1]` and `[{ id: record<foo> }, NONE]`
+/// become `[1 | { id: record<foo> }, option<NONE>]`.
+///
+/// The statement index of each position is taken from the first tuple which has that position.
+pub fn union_result_tuples(alternatives: Vec<Vec<(usize, Kind)>>) -> Vec<(usize, Kind)> {
+    let length = alternatives.iter().map(Vec::len).max().unwrap_or(0);
+
+    (0..length)
+        .map(|position| {
+            let mut index = None;
+            let mut kinds = Vec::new();
+            let mut missing = false;
+
+            for alternative in alternatives.iter() {
+                match alternative.get(position) {
+                    Some((statement_index, kind)) => {
+                        index.get_or_insert(*statement_index);
+                        kinds.push(kind.clone());
+                    }
+                    None => missing = true,
+                }
+            }
+
+            let kind = match (union_kinds(kinds), missing) {
+                (kind @ Kind::Option(..), _) | (kind, false) => kind,
+                (kind, true) => Kind::Option(Box::new(kind)),
+            };
+
+            (index.unwrap_or_default(), kind)
+        })
+        .collect()
+}
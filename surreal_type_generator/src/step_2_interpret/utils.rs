@@ -1,9 +1,12 @@
 use std::collections::BTreeMap;
 
 use crate::{kind, Kind};
-use surrealdb::sql::{Ident, Literal, Param, Part, Thing, Value};
+use surrealdb::sql::{Ident, Literal, Number, Param, Part, Thing, Value};
 
-use super::schema::{QueryState, TableFields};
+use super::{
+    get_value_return_type,
+    schema::{QueryState, TableFields},
+};
 
 pub fn get_value_table(
     what_value: &Value,
@@ -49,6 +52,11 @@ pub fn get_value_table(
                 anyhow::bail!("Expected graph traversal to end with a target table")
             }
         }
+        // eg: `type::thing("user", $id)`
+        Value::Function(_) => match get_value_return_type(what_value, &BTreeMap::new(), state)? {
+            Kind::Record(tables) if !tables.is_empty() => Ok(tables[0].0.clone()),
+            kind => anyhow::bail!("Expected record type, got: {}", kind),
+        },
         _ => anyhow::bail!("Expected record type, got: {}", what_value),
     }
 }
@@ -58,7 +66,7 @@ pub fn get_what_fields(
     state: &mut QueryState,
 ) -> Result<TableFields, anyhow::Error> {
     let table_name = get_value_table(what_value, state)?;
-    Ok(state.table_select_fields(&table_name)?)
+    state.table_select_fields(&table_name)
 }
 
 pub fn merge_into_map_recursively(
@@ -142,10 +150,155 @@ pub fn merge_into_map_recursively(
 
 pub fn is_double_optional(return_type: &Kind) -> bool {
     match return_type {
-        Kind::Option(return_type) => match **return_type {
-            Kind::Option(_) => true,
-            _ => false,
-        },
+        Kind::Option(return_type) => matches!(**return_type, Kind::Option(_)),
+        _ => false,
+    }
+}
+
+/// Combines kinds into a single simplified kind, flattening nested unions and removing duplicates.
+/// Members which are subsumed by another member are removed, eg: `"foo" | string` is `string`,
+/// and an optional member makes the whole union optional, eg: `option<string> | int` is `option<string | int>`.
+/// A union of a single kind is the kind itself, and an empty union is `null`.
+pub fn union_kinds(kinds: impl IntoIterator<Item = Kind>) -> Kind {
+    let mut members = Vec::new();
+    let mut optional = false;
+
+    for kind in kinds {
+        collect_union_members(kind, &mut members, &mut optional);
+    }
+
+    let mut members = members
+        .iter()
+        .filter(|kind| {
+            !members
+                .iter()
+                .any(|other| other != *kind && kind_subsumes(other, kind))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let kind = match members.len() {
+        0 => Kind::Null,
+        1 => members.pop().unwrap(),
+        _ => Kind::Either(members),
+    };
+
+    match optional {
+        true => Kind::Option(Box::new(kind)),
+        false => kind,
+    }
+}
+
+fn collect_union_members(kind: Kind, members: &mut Vec<Kind>, optional: &mut bool) {
+    match kind {
+        Kind::Either(kinds) => {
+            for kind in kinds {
+                collect_union_members(kind, members, optional);
+            }
+        }
+        Kind::Option(box kind) => {
+            *optional = true;
+            collect_union_members(kind, members, optional);
+        }
+        kind => {
+            if !members.contains(&kind) {
+                members.push(kind);
+            }
+        }
+    }
+}
+
+/// Returns whether every value of the `specific` kind is also a value of the `general` kind.
+fn kind_subsumes(general: &Kind, specific: &Kind) -> bool {
+    match (general, specific) {
+        (Kind::Any, _) => true,
+        (Kind::String, Kind::Literal(Literal::String(_))) => true,
+        (
+            Kind::Number,
+            Kind::Int | Kind::Float | Kind::Decimal | Kind::Literal(Literal::Number(_)),
+        ) => true,
+        (Kind::Int, Kind::Literal(Literal::Number(Number::Int(_)))) => true,
+        (Kind::Float, Kind::Literal(Literal::Number(Number::Float(_)))) => true,
+        (Kind::Decimal, Kind::Literal(Literal::Number(Number::Decimal(_)))) => true,
+        (Kind::Duration, Kind::Literal(Literal::Duration(_))) => true,
+        (Kind::Object, Kind::Literal(Literal::Object(_))) => true,
+        (Kind::Record(tables), Kind::Record(_)) => tables.is_empty(),
+        (Kind::Geometry(kinds), Kind::Geometry(_) | Kind::Point) => kinds.is_empty(),
+        _ => false,
+    }
+}
+
+/// Returns whether a value of `kind` can be passed where `target` is expected, eg: as a function argument.
+///
+/// Numbers are coerced into each other, and constants are interpreted as their base kind,
+/// so a `string` can be passed as a string literal, eg: `"active"` for `"active" | "banned"`.
+/// Kinds which aren't precise enough to be checked are allowed, eg: an `object` for `{ name: string }`.
+pub fn is_kind_assignable(kind: &Kind, target: &Kind) -> bool {
+    match (kind, target) {
+        (_, Kind::Any) | (Kind::Any, _) => true,
+        (kind, target) if kind == target || kind_subsumes(target, kind) => true,
+        (Kind::Either(kinds), target) => kinds.iter().all(|kind| is_kind_assignable(kind, target)),
+        (Kind::Option(inner), target) => {
+            split_optional_kind(target).1 && is_kind_assignable(inner, target)
+        }
+        (Kind::Null, target) => split_optional_kind(target).1,
+        (kind, Kind::Option(target)) => is_kind_assignable(kind, target),
+        (kind, Kind::Either(targets)) => targets
+            .iter()
+            .any(|target| is_kind_assignable(kind, target)),
+        (kind, target) if is_number_kind(kind) && is_number_kind(target) => true,
+        (Kind::String, Kind::Literal(Literal::String(_))) => true,
+        (Kind::Duration, Kind::Literal(Literal::Duration(_))) => true,
+        (Kind::Record(tables), Kind::Record(targets)) => {
+            tables.is_empty() || tables.iter().all(|table| targets.contains(table))
+        }
+        (
+            Kind::Array(kind, _) | Kind::Set(kind, _),
+            Kind::Array(target, _) | Kind::Set(target, _),
+        ) => is_kind_assignable(kind, target),
+        (Kind::Literal(Literal::Array(kinds)), Kind::Array(target, _) | Kind::Set(target, _)) => {
+            kinds.iter().all(|kind| is_kind_assignable(kind, target))
+        }
+        (Kind::Object, Kind::Literal(Literal::Object(_))) => true,
+        (Kind::Literal(Literal::Object(fields)), Kind::Literal(Literal::Object(targets))) => {
+            targets.iter().all(|(key, target)| {
+                is_kind_assignable(fields.get(key).unwrap_or(&Kind::Null), target)
+            })
+        }
+        (Kind::Point | Kind::Geometry(_), Kind::Geometry(_)) => true,
         _ => false,
     }
 }
+
+fn is_number_kind(kind: &Kind) -> bool {
+    matches!(
+        kind,
+        Kind::Int | Kind::Float | Kind::Decimal | Kind::Number | Kind::Literal(Literal::Number(_))
+    )
+}
+
+/// Splits a kind into the part which is not `NONE` or `NULL`, and whether it could be `NONE` or `NULL`,
+/// eg: `option<string | null>` is `(Some(string), true)`.
+pub fn split_optional_kind(kind: &Kind) -> (Option<Kind>, bool) {
+    match kind {
+        Kind::Option(inner) => (split_optional_kind(inner).0, true),
+        Kind::Null => (None, true),
+        Kind::Any => (Some(Kind::Any), true),
+        Kind::Either(kinds) => {
+            let mut present = Vec::new();
+            let mut optional = false;
+
+            for kind in kinds {
+                let (kind, kind_optional) = split_optional_kind(kind);
+                present.extend(kind);
+                optional |= kind_optional;
+            }
+
+            match present.is_empty() {
+                true => (None, optional),
+                false => (Some(union_kinds(present)), optional),
+            }
+        }
+        kind => (Some(kind.clone()), false),
+    }
+}
@@ -0,0 +1,559 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{utils::printing::indent_with, Kind};
+use surrealdb::sql::Literal;
+
+use super::{to_pascal_case, TypeData};
+
+/// Generates a Go file containing result and variable structs for every query,
+/// along with typed wrapper functions around the `surrealdb.go` client's `Query` call.
+///
+/// - `option<T>` is represented as a pointer `*T`
+/// - `T | U` is represented as a struct wrapping a tagged interface, where objects are decoded using a
+///   discriminator such as a literal `kind` field or the table of their `id`
+pub fn generate_go_output(types: &[TypeData], package: &str) -> Result<String, anyhow::Error> {
+    let mut output = String::new();
+    let mut definitions = GoDefinitions::default();
+
+    output.push_str("// Code generated by surreal-codegen. DO NOT EDIT.\n\n");
+    output.push_str(&format!("package {}\n\n", package));
+    output.push_str(
+        "import (\n\t\"fmt\"\n\n\tsurrealdb \"github.com/surrealdb/surrealdb.go\"\n\t\"github.com/surrealdb/surrealdb.go/pkg/models\"\n)\n",
+    );
+
+    for TypeData {
+        name,
+        statements,
+        return_type,
+        variables,
+        named_results,
+        ..
+    } in types
+    {
+        // reserve the names used by the query, so that named results can't collide with them,
+        // eg: `-- @name result` generates `GetUserResult2` rather than a second `GetUserResult`
+        for suffix in ["", "Query", "Result", "Variables"] {
+            definitions.reserve_exact(&format!("{}{}", name, suffix))?;
+        }
+
+        output.push_str(&format!(
+            "\nconst {}Query = {}\n",
+            name,
+            // Comment the query name so that they are distinguished between identical queries
+            serde_json::to_string(&format!("-- {}\n{}", name, statements))?
+        ));
+
+        let mut field_names = BTreeSet::new();
+        let result_fields = return_type
+            .iter()
+            .enumerate()
+            .map(|(index, kind)| {
+                let mut field_name = match named_results.iter().find(|(_, i)| *i == index) {
+                    Some((key, _)) => to_go_identifier(key),
+                    None => format!("Result{}", index),
+                };
+                // eg: `-- @name result_1` on the first statement, and an unnamed second statement
+                if !field_names.insert(field_name.clone()) {
+                    field_name = format!("{}{}", field_name, index);
+                    field_names.insert(field_name.clone());
+                }
+                let type_name =
+                    go_type(kind, &format!("{}{}", name, field_name), &mut definitions)?;
+                Ok((field_name, type_name))
+            })
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+        definitions.push(format!(
+            "type {}Result struct {{\n{}\n}}",
+            name,
+            indent_with(
+                &result_fields
+                    .iter()
+                    .map(|(field_name, type_name)| format!("{} {}", field_name, type_name))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                "\t"
+            )
+        ));
+
+        if !variables.is_empty() {
+            go_struct(&format!("{}Variables", name), variables, &mut definitions)?;
+        }
+
+        for definition in std::mem::take(&mut definitions.definitions) {
+            output.push('\n');
+            output.push_str(&definition);
+            output.push('\n');
+        }
+
+        output.push('\n');
+        output.push_str(&go_query_function(name, variables, &result_fields));
+    }
+
+    output.push_str(HELPER_FUNCTIONS);
+
+    Ok(output)
+}
+
+/// The type definitions of the generated file, and the type names which are already in use
+#[derive(Default)]
+struct GoDefinitions {
+    definitions: Vec<String>,
+    names: BTreeSet<String>,
+}
+
+impl GoDefinitions {
+    /// Reserves a unique type name, appending a number if the name is already in use, eg: `GetUserResult2`
+    fn reserve(&mut self, name: &str) -> String {
+        let mut unique = name.to_string();
+        let mut count = 1;
+        while !self.names.insert(unique.clone()) {
+            count += 1;
+            unique = format!("{}{}", name, count);
+        }
+        unique
+    }
+
+    fn reserve_exact(&mut self, name: &str) -> Result<(), anyhow::Error> {
+        if !self.names.insert(name.to_string()) {
+            anyhow::bail!("The Go name `{}` is generated more than once", name);
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, definition: String) {
+        self.definitions.push(definition);
+    }
+}
+
+fn go_query_function(
+    name: &str,
+    variables: &BTreeMap<String, Kind>,
+    result_fields: &[(String, String)],
+) -> String {
+    let mut lines = Vec::new();
+
+    match variables.is_empty() {
+        true => {
+            lines.push(format!(
+                "func {}(db *surrealdb.DB) (*{}Result, error) {{",
+                name, name
+            ));
+            lines.push("\tvars := map[string]interface{}{}".into());
+        }
+        false => {
+            lines.push(format!(
+                "func {}(db *surrealdb.DB, variables {}Variables) (*{}Result, error) {{",
+                name, name, name
+            ));
+            lines.push("\tvars := map[string]interface{}{}".into());
+            for (key, kind) in variables {
+                let field_name = to_go_identifier(key);
+                match kind {
+                    // Omit optional variables, so they are passed as `NONE` rather than `NULL`
+                    Kind::Option(_) => lines.push(format!(
+                        "\tif variables.{} != nil {{\n\t\tvars[\"{}\"] = variables.{}\n\t}}",
+                        field_name, key, field_name
+                    )),
+                    _ => lines.push(format!("\tvars[\"{}\"] = variables.{}", key, field_name)),
+                }
+            }
+        }
+    }
+
+    lines.push(format!(
+        "\tresults, err := surrealdb.Query[any](db, {}Query, vars)",
+        name
+    ));
+    lines.push("\tif err != nil {\n\t\treturn nil, err\n\t}".into());
+    lines.push(format!(
+        "\tif len(*results) != {} {{\n\t\treturn nil, fmt.Errorf(\"expected {} results, got %d\", len(*results))\n\t}}",
+        result_fields.len(),
+        result_fields.len()
+    ));
+    lines.push(format!("\tvar result {}Result", name));
+
+    for (index, (field_name, type_name)) in result_fields.iter().enumerate() {
+        lines.push(format!(
+            "\tif (*results)[{index}].Status != \"OK\" {{\n\t\treturn nil, fmt.Errorf(\"result {index}: %s: %v\", (*results)[{index}].Status, (*results)[{index}].Result)\n\t}}"
+        ));
+        lines.push(format!(
+            "\tif result.{field_name}, err = decodeResult[{type_name}]((*results)[{index}].Result); err != nil {{\n\t\treturn nil, err\n\t}}"
+        ));
+    }
+
+    lines.push("\treturn &result, nil\n}\n".into());
+
+    lines.join("\n")
+}
+
+/// Returns the Go type for a `Kind`, pushing any named struct or union definitions it needs into `definitions`.
+/// `type_name` is used as the name of any generated struct or union, unless it is already in use.
+fn go_type(
+    kind: &Kind,
+    type_name: &str,
+    definitions: &mut GoDefinitions,
+) -> Result<String, anyhow::Error> {
+    Ok(match kind {
+        Kind::Any | Kind::Object | Kind::Null => "any".into(),
+        Kind::String | Kind::Uuid => "string".into(),
+        Kind::Int => "int64".into(),
+        Kind::Float | Kind::Number => "float64".into(),
+        Kind::Decimal => "string".into(),
+        Kind::Bool => "bool".into(),
+        Kind::Bytes => "[]byte".into(),
+        Kind::Datetime => "models.CustomDateTime".into(),
+        Kind::Duration => "models.CustomDuration".into(),
+        Kind::Record(_) => "models.RecordID".into(),
+        Kind::Array(inner, ..) | Kind::Set(inner, ..) => {
+            format!(
+                "[]{}",
+                go_type(inner, &format!("{}Item", type_name), definitions)?
+            )
+        }
+        Kind::Option(inner) => go_optional_type(go_type(inner, type_name, definitions)?),
+        Kind::Either(kinds) => {
+            let non_null = kinds
+                .iter()
+                .filter(|kind| !matches!(kind, Kind::Null))
+                .collect::<Vec<_>>();
+
+            match non_null.as_slice() {
+                [] => "any".into(),
+                // `T | NULL` is represented the same as `option<T>`
+                [kind] => go_optional_type(go_type(kind, type_name, definitions)?),
+                kinds if kinds.iter().all(|kind| is_string_kind(kind)) => "string".into(),
+                kinds if kinds.iter().all(|kind| is_number_kind(kind)) => "float64".into(),
+                variants => {
+                    let type_name = definitions.reserve(type_name);
+                    go_union(&type_name, variants, definitions)?;
+                    match non_null.len() == kinds.len() {
+                        true => type_name,
+                        false => format!("*{}", type_name),
+                    }
+                }
+            }
+        }
+        Kind::Literal(Literal::String(_)) => "string".into(),
+        Kind::Literal(Literal::Number(_)) => "float64".into(),
+        Kind::Literal(Literal::Duration(_)) => "models.CustomDuration".into(),
+        Kind::Literal(Literal::Array(_)) => "[]any".into(),
+        Kind::Literal(Literal::Object(fields)) => {
+            let type_name = definitions.reserve(type_name);
+            go_struct(&type_name, fields, definitions)?;
+            type_name
+        }
+        // Catch all
+        kind => anyhow::bail!("Kind {:?} not yet supported for Go", kind),
+    })
+}
+
+fn go_optional_type(type_name: String) -> String {
+    match type_name.starts_with("[]") || type_name == "any" {
+        // slices and interfaces are already nilable
+        true => type_name,
+        false => format!("*{}", type_name),
+    }
+}
+
+fn is_string_kind(kind: &Kind) -> bool {
+    matches!(kind, Kind::String | Kind::Literal(Literal::String(_)))
+}
+
+fn is_number_kind(kind: &Kind) -> bool {
+    matches!(
+        kind,
+        Kind::Number | Kind::Float | Kind::Literal(Literal::Number(_))
+    )
+}
+
+/// Pushes a struct definition, where `type_name` has already been reserved
+fn go_struct(
+    type_name: &str,
+    fields: &BTreeMap<String, Kind>,
+    definitions: &mut GoDefinitions,
+) -> Result<(), anyhow::Error> {
+    let mut lines = Vec::new();
+
+    for (key, kind) in fields {
+        let field_name = to_go_identifier(key);
+        let field_type = go_type(kind, &format!("{}{}", type_name, field_name), definitions)?;
+        lines.push(format!(
+            "{} {} `json:\"{}{}\"`",
+            field_name,
+            field_type,
+            key,
+            match kind {
+                Kind::Option(_) => ",omitempty",
+                _ => "",
+            }
+        ));
+    }
+
+    definitions.push(format!(
+        "type {} struct {{\n{}\n}}",
+        type_name,
+        indent_with(&lines.join("\n"), "\t")
+    ));
+
+    Ok(())
+}
+
+/// Pushes a union definition, where `type_name` has already been reserved.
+///
+/// Values which aren't objects are decoded into the first variant of the same type,
+/// and objects are decoded using a discriminator, which is either a string literal field
+/// such as `kind: "a" | "b"`, the table of their `id`, or otherwise the fields they have.
+fn go_union(
+    type_name: &str,
+    kinds: &[&Kind],
+    definitions: &mut GoDefinitions,
+) -> Result<(), anyhow::Error> {
+    let interface_name = definitions.reserve(&format!("{}Variant", type_name));
+
+    let mut variants = Vec::new();
+    let mut objects = Vec::new();
+
+    for (index, kind) in kinds.iter().enumerate() {
+        let variant_name = format!("{}{}", type_name, index);
+
+        let variant_name = match kind {
+            Kind::Literal(Literal::Object(fields)) => {
+                let variant_name = go_type(kind, &variant_name, definitions)?;
+                objects.push((variant_name.clone(), fields));
+                variant_name
+            }
+            // other types need a named wrapper to implement the interface
+            kind => {
+                let variant_name = definitions.reserve(&variant_name);
+                let variant_type = go_type(kind, &variant_name, definitions)?;
+                definitions.push(format!("type {} {}", variant_name, variant_type));
+                variants.push(variant_name.clone());
+                variant_name
+            }
+        };
+
+        definitions.push(format!(
+            "func ({}) is{}() {{}}",
+            variant_name, interface_name
+        ));
+    }
+
+    definitions.push(format!(
+        "// {} is one of: {}\ntype {} struct {{\n\tValue {}\n}}\n\ntype {} interface {{\n\tis{}()\n}}",
+        type_name,
+        variants
+            .iter()
+            .chain(objects.iter().map(|(name, _)| name))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", "),
+        type_name,
+        interface_name,
+        interface_name,
+        interface_name,
+    ));
+
+    let mut decoders = variants
+        .iter()
+        .enumerate()
+        .map(|(index, variant)| format!(
+            "\tvar v{index} {variant}\n\tif err := (models.CborUnmarshaler{{}}).Unmarshal(data, &v{index}); err == nil {{\n\t\tu.Value = v{index}\n\t\treturn nil\n\t}}"
+        ))
+        .collect::<Vec<_>>();
+
+    if !objects.is_empty() {
+        decoders.push(go_object_decoder(&objects));
+    }
+
+    definitions.push(format!(
+        "// UnmarshalCBOR decodes the value into the variant which matches it\nfunc (u *{}) UnmarshalCBOR(data []byte) error {{\n{}\n\treturn fmt.Errorf(\"value does not match any variant of {}\")\n}}",
+        type_name,
+        decoders.join("\n"),
+        type_name,
+    ));
+
+    definitions.push(format!(
+        "// MarshalCBOR encodes the variant value, rather than the wrapper struct\nfunc (u {}) MarshalCBOR() ([]byte, error) {{\n\treturn (models.CborMarshaler{{}}).Marshal(u.Value)\n}}",
+        type_name,
+    ));
+
+    Ok(())
+}
+
+/// Returns the Go code which decodes an object into one of the object variants of a union
+fn go_object_decoder(objects: &[(String, &BTreeMap<String, Kind>)]) -> String {
+    let decode = |variant: &str, indent: &str| {
+        indent_with(
+            &format!(
+                "var value {variant}\nif err := (models.CborUnmarshaler{{}}).Unmarshal(data, &value); err != nil {{\n\treturn err\n}}\nu.Value = value\nreturn nil"
+            ),
+            indent,
+        )
+    };
+
+    // a single object doesn't need a discriminator, as only objects can be decoded into a struct
+    if let [(variant, _)] = objects {
+        return format!(
+            "\tvar object {variant}\n\tif err := (models.CborUnmarshaler{{}}).Unmarshal(data, &object); err == nil {{\n\t\tu.Value = object\n\t\treturn nil\n\t}}"
+        );
+    }
+
+    if let Some((field, tags)) = get_tag_field(objects) {
+        return format!(
+            "\tvar tag struct {{\n\t\tTag string `json:\"{}\"`\n\t}}\n\tif err := (models.CborUnmarshaler{{}}).Unmarshal(data, &tag); err == nil {{\n\t\tswitch tag.Tag {{\n{}\n\t\t}}\n\t}}",
+            field,
+            objects
+                .iter()
+                .zip(tags)
+                .map(|((variant, _), tag)| format!(
+                    "\t\tcase {}:\n{}",
+                    serde_json::Value::from(tag),
+                    decode(variant, "\t\t\t")
+                ))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+
+    if let Some(tables) = get_record_tables(objects) {
+        return format!(
+            "\tvar record struct {{\n\t\tID models.RecordID `json:\"id\"`\n\t}}\n\tif err := (models.CborUnmarshaler{{}}).Unmarshal(data, &record); err == nil {{\n\t\tswitch record.ID.Table {{\n{}\n\t\t}}\n\t}}",
+            objects
+                .iter()
+                .zip(tables)
+                .map(|((variant, _), tables)| format!(
+                    "\t\tcase {}:\n{}",
+                    tables
+                        .iter()
+                        .map(|table| serde_json::Value::from(table.as_str()).to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    decode(variant, "\t\t\t")
+                ))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+
+    // otherwise decode into the first object which has every required field, and no unknown fields
+    format!(
+        "\tvar fields map[string]any\n\tif err := (models.CborUnmarshaler{{}}).Unmarshal(data, &fields); err == nil {{\n{}\n\t}}",
+        objects
+            .iter()
+            .map(|(variant, fields)| {
+                let required = fields
+                    .iter()
+                    .filter(|(_, kind)| !matches!(kind, Kind::Option(_)))
+                    .map(|(key, _)| key.as_str());
+                format!(
+                    "\t\tif hasFields(fields, {}, {}) {{\n{}\n\t\t}}",
+                    go_string_slice(required),
+                    go_string_slice(fields.keys().map(String::as_str)),
+                    decode(variant, "\t\t\t")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    )
+}
+
+/// Returns a string literal field which every object has, with a different value for each object,
+/// eg: `kind` for `{ kind: "a", ... } | { kind: "b", ... }`
+fn get_tag_field(objects: &[(String, &BTreeMap<String, Kind>)]) -> Option<(String, Vec<String>)> {
+    let (_, first) = objects.first()?;
+
+    first.keys().find_map(|field| {
+        let tags = objects
+            .iter()
+            .map(|(_, fields)| match fields.get(field) {
+                Some(Kind::Literal(Literal::String(tag))) => Some(tag.as_str().to_string()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let unique = tags.iter().collect::<BTreeSet<_>>();
+        match unique.len() == tags.len() {
+            true => Some((field.clone(), tags)),
+            false => None,
+        }
+    })
+}
+
+/// Returns the tables of the `id` field of every object, if they are all records of different tables
+fn get_record_tables(objects: &[(String, &BTreeMap<String, Kind>)]) -> Option<Vec<Vec<String>>> {
+    let tables = objects
+        .iter()
+        .map(|(_, fields)| match fields.get("id") {
+            Some(Kind::Record(tables)) if !tables.is_empty() => Some(
+                tables
+                    .iter()
+                    .map(|table| table.to_string())
+                    .collect::<Vec<_>>(),
+            ),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let unique = tables.iter().flatten().collect::<BTreeSet<_>>();
+    match unique.len() == tables.iter().map(Vec::len).sum::<usize>() {
+        true => Some(tables),
+        false => None,
+    }
+}
+
+fn go_string_slice<'a>(values: impl Iterator<Item = &'a str>) -> String {
+    format!(
+        "[]string{{{}}}",
+        values
+            .map(|value| serde_json::Value::from(value).to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// Converts a field name into an exported Go identifier, eg: `created_at` -> `CreatedAt`
+fn to_go_identifier(name: &str) -> String {
+    let identifier = to_pascal_case(name);
+
+    match identifier.as_str() {
+        "Id" => "ID".into(),
+        "" => "Field".into(),
+        _ if identifier.starts_with(|c: char| c.is_ascii_digit()) => format!("Field{}", identifier),
+        _ => identifier,
+    }
+}
+
+const HELPER_FUNCTIONS: &str = r#"
+func decodeResult[T any](value any) (T, error) {
+	var result T
+	data, err := (models.CborMarshaler{}).Marshal(value)
+	if err != nil {
+		return result, err
+	}
+	err = (models.CborUnmarshaler{}).Unmarshal(data, &result)
+	return result, err
+}
+
+// hasFields returns whether an object has every required field, and no fields which aren't known
+func hasFields(object map[string]any, required []string, known []string) bool {
+	for _, field := range required {
+		if _, ok := object[field]; !ok {
+			return false
+		}
+	}
+	for field := range object {
+		found := false
+		for _, key := range known {
+			if key == field {
+				found = true
+				break
+			}
+		}
+		if !found {
+			return false
+		}
+	}
+	return true
+}
+"#;
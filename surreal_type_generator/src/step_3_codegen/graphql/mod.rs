@@ -0,0 +1,474 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use crate::{kind, utils::printing::indent, Kind};
+use surrealdb::sql::{
+    statements::{ForeachStatement, IfelseStatement, SelectStatement},
+    Entry, Expression, Field, Literal, Statement, Subquery, Value,
+};
+
+use crate::step_2_interpret::{QueryState, SchemaState};
+
+use super::{to_camel_case, to_pascal_case, TypeData};
+
+/// Generates a GraphQL SDL schema from the tables and views in the schema,
+/// with `record<table>` links as object references, and every query as a `Query` or `Mutation` field.
+///
+/// Queries containing a `CREATE`, `UPDATE`, `UPSERT`, `DELETE`, `INSERT` or `RELATE` statement are mutations.
+pub fn generate_graphql_schema(
+    types: &[TypeData],
+    schema: Arc<SchemaState>,
+) -> Result<String, anyhow::Error> {
+    let mut state = QueryState::new(schema.clone(), BTreeMap::new());
+
+    let mut tables = BTreeMap::new();
+    for name in schema
+        .schema
+        .tables
+        .keys()
+        .chain(schema.schema.views.keys())
+    {
+        tables.insert(name.clone(), state.table_select_fields(name)?);
+    }
+
+    let mut generator = GraphQLGenerator {
+        tables: &tables,
+        definitions: BTreeMap::new(),
+        scalars: BTreeSet::new(),
+    };
+
+    for (name, fields) in &tables {
+        generator.object_type(&to_pascal_case(name), fields)?;
+    }
+
+    let mut queries = Vec::new();
+    let mut mutations = Vec::new();
+
+    for TypeData {
+        name,
+        statements,
+        return_type,
+        variables,
+        named_results,
+        ..
+    } in types
+    {
+        let mut arguments = Vec::new();
+        for (key, kind) in variables {
+            arguments.push(format!(
+                "{}: {}",
+                to_graphql_name(key),
+                generator.input_type(kind, &format!("{}{}Input", name, to_pascal_case(key)))?
+            ));
+        }
+
+        let result_type = match return_type.as_slice() {
+            [kind] => generator.output_type(kind, &format!("{}Result", name))?,
+            results => {
+                let mut fields = BTreeMap::new();
+                for (index, kind) in results.iter().enumerate() {
+                    match named_results.iter().find(|(_, i)| *i == index) {
+                        Some((key, _)) => {
+                            fields.insert(key.clone(), kind.clone());
+                        }
+                        // Only named statements are exposed when any statement is named
+                        None if !named_results.is_empty() => {}
+                        None => {
+                            fields.insert(format!("result{}", index), kind.clone());
+                        }
+                    }
+                }
+                generator.output_type(&kind!(Obj fields), &format!("{}Result", name))?
+            }
+        };
+
+        let field = format!(
+            "{}{}: {}",
+            to_camel_case(name),
+            match arguments.is_empty() {
+                true => String::new(),
+                false => format!("({})", arguments.join(", ")),
+            },
+            result_type
+        );
+
+        match statements.iter().any(is_mutation) {
+            true => mutations.push(field),
+            false => queries.push(field),
+        }
+    }
+
+    let mut output = String::new();
+
+    for scalar in &generator.scalars {
+        output.push_str(&format!("scalar {}\n", scalar));
+    }
+
+    for definition in generator.definitions.values() {
+        output.push('\n');
+        output.push_str(definition);
+        output.push('\n');
+    }
+
+    if !queries.is_empty() {
+        output.push_str(&format!(
+            "\ntype Query {{\n{}\n}}\n",
+            indent(&queries.join("\n"))
+        ));
+    }
+
+    if !mutations.is_empty() {
+        output.push_str(&format!(
+            "\ntype Mutation {{\n{}\n}}\n",
+            indent(&mutations.join("\n"))
+        ));
+    }
+
+    Ok(output)
+}
+
+/// Returns whether a statement writes to the database, including any statements nested in
+/// blocks, subqueries, `LET`, `IF`, `FOR` or `RETURN` statements, eg: `LET $user = CREATE user;`
+fn is_mutation(statement: &Statement) -> bool {
+    match statement {
+        Statement::Create(_)
+        | Statement::Update(_)
+        | Statement::Upsert(_)
+        | Statement::Delete(_)
+        | Statement::Insert(_)
+        | Statement::Relate(_) => true,
+        Statement::Set(set) => value_is_mutation(&set.what),
+        Statement::Ifelse(ifelse) => ifelse_is_mutation(ifelse),
+        Statement::Foreach(foreach) => foreach_is_mutation(foreach),
+        Statement::Output(output) => value_is_mutation(&output.what),
+        Statement::Throw(throw) => value_is_mutation(&throw.error),
+        Statement::Select(select) => select_is_mutation(select),
+        Statement::Value(value) => value_is_mutation(value),
+        _ => false,
+    }
+}
+
+fn entry_is_mutation(entry: &Entry) -> bool {
+    match entry {
+        Entry::Create(_)
+        | Entry::Update(_)
+        | Entry::Upsert(_)
+        | Entry::Delete(_)
+        | Entry::Insert(_)
+        | Entry::Relate(_) => true,
+        Entry::Set(set) => value_is_mutation(&set.what),
+        Entry::Ifelse(ifelse) => ifelse_is_mutation(ifelse),
+        Entry::Foreach(foreach) => foreach_is_mutation(foreach),
+        Entry::Output(output) => value_is_mutation(&output.what),
+        Entry::Throw(throw) => value_is_mutation(&throw.error),
+        Entry::Select(select) => select_is_mutation(select),
+        Entry::Value(value) => value_is_mutation(value),
+        _ => false,
+    }
+}
+
+fn value_is_mutation(value: &Value) -> bool {
+    match value {
+        Value::Subquery(box subquery) => match subquery {
+            Subquery::Create(_)
+            | Subquery::Update(_)
+            | Subquery::Upsert(_)
+            | Subquery::Delete(_)
+            | Subquery::Insert(_)
+            | Subquery::Relate(_) => true,
+            Subquery::Ifelse(ifelse) => ifelse_is_mutation(ifelse),
+            Subquery::Output(output) => value_is_mutation(&output.what),
+            Subquery::Select(select) => select_is_mutation(select),
+            Subquery::Value(value) => value_is_mutation(value),
+            _ => false,
+        },
+        Value::Block(box block) => block.0.iter().any(entry_is_mutation),
+        Value::Expression(box Expression::Binary { l, r, .. }) => {
+            value_is_mutation(l) || value_is_mutation(r)
+        }
+        Value::Expression(box Expression::Unary { v, .. }) => value_is_mutation(v),
+        Value::Array(array) => array.iter().any(value_is_mutation),
+        Value::Object(object) => object.values().any(value_is_mutation),
+        Value::Function(box function) => function.args().iter().any(value_is_mutation),
+        Value::Cast(box cast) => value_is_mutation(&cast.1),
+        _ => false,
+    }
+}
+
+fn ifelse_is_mutation(ifelse: &IfelseStatement) -> bool {
+    ifelse
+        .exprs
+        .iter()
+        .any(|(cond, then)| value_is_mutation(cond) || value_is_mutation(then))
+        || ifelse.close.as_ref().is_some_and(value_is_mutation)
+}
+
+fn foreach_is_mutation(foreach: &ForeachStatement) -> bool {
+    value_is_mutation(&foreach.range) || foreach.block.0.iter().any(entry_is_mutation)
+}
+
+fn select_is_mutation(select: &SelectStatement) -> bool {
+    select.expr.0.iter().any(|field| match field {
+        Field::Single { expr, .. } => value_is_mutation(expr),
+        _ => false,
+    }) || select.what.iter().any(value_is_mutation)
+        || select
+            .cond
+            .as_ref()
+            .is_some_and(|cond| value_is_mutation(&cond.0))
+}
+
+struct GraphQLGenerator<'a> {
+    /// The select fields of every table and view
+    tables: &'a BTreeMap<String, BTreeMap<String, Kind>>,
+    definitions: BTreeMap<String, String>,
+    scalars: BTreeSet<String>,
+}
+
+impl GraphQLGenerator<'_> {
+    fn scalar(&mut self, name: &str) -> String {
+        self.scalars.insert(name.to_string());
+        name.to_string()
+    }
+
+    /// Adds a type definition, erroring if a different type has already been generated with the same name,
+    /// eg: `record<a | b>` and a table named `a_or_b` are both `AOrB`
+    fn define(&mut self, type_name: &str, definition: String) -> Result<String, anyhow::Error> {
+        match self.definitions.get(type_name) {
+            Some(existing) if *existing != definition => anyhow::bail!(
+                "GraphQL type name `{}` is generated for more than one type",
+                type_name
+            ),
+            _ => {
+                self.definitions.insert(type_name.to_string(), definition);
+            }
+        }
+
+        Ok(type_name.to_string())
+    }
+
+    fn union_type(&mut self, type_name: &str, members: &[String]) -> Result<String, anyhow::Error> {
+        self.define(
+            type_name,
+            format!("union {} = {}", type_name, members.join(" | ")),
+        )
+    }
+
+    fn object_type(
+        &mut self,
+        type_name: &str,
+        fields: &BTreeMap<String, Kind>,
+    ) -> Result<String, anyhow::Error> {
+        let mut lines = Vec::new();
+        for (key, kind) in fields {
+            lines.push(format!(
+                "{}: {}",
+                to_graphql_name(key),
+                match key.as_str() {
+                    "id" => "ID!".to_string(),
+                    _ =>
+                        self.output_type(kind, &format!("{}{}", type_name, to_pascal_case(key)))?,
+                }
+            ));
+        }
+
+        self.define(
+            type_name,
+            format!("type {} {{\n{}\n}}", type_name, indent(&lines.join("\n"))),
+        )
+    }
+
+    /// Returns the GraphQL output type for a `Kind`, `type_name` is used to name any generated object types.
+    fn output_type(&mut self, kind: &Kind, type_name: &str) -> Result<String, anyhow::Error> {
+        Ok(match kind {
+            Kind::Option(inner) => nullable(self.output_type(inner, type_name)?),
+            Kind::Either(kinds) if kinds.iter().any(|kind| matches!(kind, Kind::Null)) => {
+                let kinds = kinds
+                    .iter()
+                    .filter(|kind| !matches!(kind, Kind::Null))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                match kinds.len() {
+                    0 => nullable(self.scalar("JSON")),
+                    1 => nullable(self.output_type(&kinds[0], type_name)?),
+                    _ => nullable(self.output_type(&Kind::Either(kinds), type_name)?),
+                }
+            }
+            Kind::Either(kinds) => {
+                // union members must be object types, so `record<a | b>` contributes `A` and `B`
+                let kinds = kinds
+                    .iter()
+                    .flat_map(|kind| match kind {
+                        Kind::Record(tables) if tables.len() > 1 => tables
+                            .iter()
+                            .map(|table| Kind::Record(vec![table.clone()]))
+                            .collect(),
+                        kind => vec![kind.clone()],
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut members = Vec::new();
+                for (index, kind) in kinds.iter().enumerate() {
+                    match kind {
+                        Kind::Literal(Literal::Object(_)) | Kind::Record(_) => {
+                            match self.output_type(kind, &format!("{}{}", type_name, index))? {
+                                // links to unknown tables can't be part of a union
+                                member if member == "ID!" => {
+                                    members.clear();
+                                    break;
+                                }
+                                member if members.contains(&nullable(member.clone())) => {}
+                                member => members.push(nullable(member)),
+                            }
+                        }
+                        _ => {
+                            members.clear();
+                            break;
+                        }
+                    }
+                }
+
+                if !members.is_empty() {
+                    // unions of object types
+                    format!("{}!", self.union_type(type_name, &members)?)
+                } else if kinds
+                    .iter()
+                    .all(|kind| matches!(kind, Kind::String | Kind::Literal(Literal::String(_))))
+                {
+                    "String!".into()
+                } else {
+                    format!("{}!", self.scalar("JSON"))
+                }
+            }
+            Kind::Array(inner, ..) | Kind::Set(inner, ..) => {
+                format!("[{}]!", self.output_type(inner, type_name)?)
+            }
+            Kind::Record(tables) => match tables.as_slice() {
+                [table] if self.tables.contains_key(table.as_str()) => {
+                    format!("{}!", to_pascal_case(table))
+                }
+                tables
+                    if !tables.is_empty()
+                        && tables
+                            .iter()
+                            .all(|table| self.tables.contains_key(table.as_str())) =>
+                {
+                    let members = tables
+                        .iter()
+                        .map(|table| to_pascal_case(table))
+                        .collect::<Vec<_>>();
+                    format!("{}!", self.union_type(&members.join("Or"), &members)?)
+                }
+                _ => "ID!".into(),
+            },
+            Kind::Literal(Literal::Object(fields)) => {
+                // use the table type if the object is exactly a table's fields, eg: `SELECT * FROM user`
+                match self.tables.iter().find(|(_, table)| *table == fields) {
+                    Some((table, _)) => format!("{}!", to_pascal_case(table)),
+                    None => format!("{}!", self.object_type(type_name, fields)?),
+                }
+            }
+            kind => format!("{}!", self.scalar_type(kind)?),
+        })
+    }
+
+    /// Returns the GraphQL input type for a `Kind`, `type_name` is used to name any generated input types.
+    fn input_type(&mut self, kind: &Kind, type_name: &str) -> Result<String, anyhow::Error> {
+        Ok(match kind {
+            Kind::Option(inner) => nullable(self.input_type(inner, type_name)?),
+            Kind::Either(kinds) => {
+                let non_null = kinds
+                    .iter()
+                    .filter(|kind| !matches!(kind, Kind::Null))
+                    .collect::<Vec<_>>();
+
+                let input_type = match non_null.as_slice() {
+                    [kind] => self.input_type(kind, type_name)?,
+                    // GraphQL coerces a single value into a list, so `T | array<T>` is just `[T]`
+                    [item, Kind::Array(box array_item, ..)]
+                    | [Kind::Array(box array_item, ..), item]
+                        if *item == array_item =>
+                    {
+                        format!("[{}]!", self.input_type(item, type_name)?)
+                    }
+                    kinds
+                        if kinds.iter().all(|kind| {
+                            matches!(kind, Kind::String | Kind::Literal(Literal::String(_)))
+                        }) =>
+                    {
+                        "String!".into()
+                    }
+                    // GraphQL does not support input unions
+                    _ => format!("{}!", self.scalar("JSON")),
+                };
+
+                match non_null.len() == kinds.len() {
+                    true => input_type,
+                    false => nullable(input_type),
+                }
+            }
+            Kind::Array(inner, ..) | Kind::Set(inner, ..) => {
+                format!("[{}]!", self.input_type(inner, type_name)?)
+            }
+            Kind::Record(_) => "ID!".into(),
+            Kind::Literal(Literal::Object(fields)) => {
+                let mut lines = Vec::new();
+                for (key, kind) in fields {
+                    lines.push(format!(
+                        "{}: {}",
+                        to_graphql_name(key),
+                        self.input_type(kind, &format!("{}{}", type_name, to_pascal_case(key)))?
+                    ));
+                }
+                let input_name = self.define(
+                    type_name,
+                    format!("input {} {{\n{}\n}}", type_name, indent(&lines.join("\n"))),
+                )?;
+                format!("{}!", input_name)
+            }
+            kind => format!("{}!", self.scalar_type(kind)?),
+        })
+    }
+
+    fn scalar_type(&mut self, kind: &Kind) -> Result<String, anyhow::Error> {
+        Ok(match kind {
+            Kind::String | Kind::Uuid | Kind::Literal(Literal::String(_)) => "String".into(),
+            // SurrealDB ints are 64-bit, which don't fit in a GraphQL `Int`
+            Kind::Int => self.scalar("Int64"),
+            Kind::Float | Kind::Number | Kind::Literal(Literal::Number(_)) => "Float".into(),
+            Kind::Bool => "Boolean".into(),
+            Kind::Datetime => self.scalar("DateTime"),
+            Kind::Duration | Kind::Literal(Literal::Duration(_)) => self.scalar("Duration"),
+            Kind::Decimal => self.scalar("Decimal"),
+            // geometries are sent as GeoJSON objects
+            Kind::Geometry(_) | Kind::Point => self.scalar("Geometry"),
+            Kind::Bytes => self.scalar("Bytes"),
+            Kind::Any | Kind::Object | Kind::Null | Kind::Literal(Literal::Array(_)) => {
+                self.scalar("JSON")
+            }
+            // Catch all
+            kind => anyhow::bail!("Kind {:?} not yet supported for GraphQL", kind),
+        })
+    }
+}
+
+fn nullable(type_name: String) -> String {
+    type_name.trim_end_matches('!').to_string()
+}
+
+fn to_graphql_name(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c,
+            false => '_',
+        })
+        .collect::<String>();
+
+    match name.starts_with(|c: char| c.is_ascii_digit()) {
+        true => format!("_{}", name),
+        false => name,
+    }
+}
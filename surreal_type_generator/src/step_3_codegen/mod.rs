@@ -1,3 +1,5 @@
+pub mod go;
+pub mod graphql;
 pub mod typescript;
 
 use std::{
@@ -21,6 +23,8 @@ pub struct TypeData {
     pub statements: Statements,
     pub return_type: Vec<Kind>,
     pub variables: BTreeMap<String, Kind>,
+    /// Statements named with a `-- @name foo` comment, and the index of their result
+    pub named_results: Vec<(String, usize)>,
 }
 
 pub fn generate_type_info(
@@ -41,6 +45,7 @@ pub fn generate_type_info(
             s
         },
         variables: result.variables,
+        named_results: result.named_results,
     })
 }
 
@@ -70,11 +75,40 @@ fn filename_to_camel_case(filename: &str) -> Result<String, anyhow::Error> {
     Ok(camel_case_name)
 }
 
+/// Converts a `snake_case` name into `PascalCase`, eg: `get_user` -> `GetUser`
+pub(crate) fn to_pascal_case(name: &str) -> String {
+    let mut pascal_case_name = String::new();
+    let mut new_word = true;
+
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            new_word = true;
+        } else if new_word {
+            pascal_case_name.push(c.to_ascii_uppercase());
+            new_word = false;
+        } else {
+            pascal_case_name.push(c);
+        }
+    }
+
+    pascal_case_name
+}
+
+/// Converts a `PascalCase` name into `camelCase`, eg: `GetUser` -> `getUser`
+pub(crate) fn to_camel_case(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
 pub struct QueryResult {
     pub statements: Vec<Statement>,
     pub variables: BTreeMap<String, Kind>,
     pub state: QueryState,
     pub return_types: Vec<Kind>,
+    pub named_results: Vec<(String, usize)>,
 }
 
 pub fn query_to_return_type(query: &str, schema: &str) -> anyhow::Result<QueryResult> {
@@ -85,8 +119,22 @@ pub fn output_query_type(query: &str, schema: Arc<SchemaState>) -> anyhow::Resul
     let parsed_query = crate::step_1_parse_sql::parse_query(query)?;
     let mut query_state = QueryState::new(schema, parsed_query.casted_parameters);
 
+    let results = interpret_query(&parsed_query.statements, &mut query_state)?;
+
+    let mut named_results = Vec::new();
+    for (statement_index, name) in parsed_query.statement_names {
+        match results
+            .iter()
+            .position(|(index, _)| *index == statement_index)
+        {
+            Some(result_index) => named_results.push((name, result_index)),
+            None => anyhow::bail!("Statement named `{}` does not produce a result", name),
+        }
+    }
+
     Ok(QueryResult {
-        return_types: interpret_query(&parsed_query.statements, &mut query_state)?,
+        return_types: results.into_iter().map(|(_, kind)| kind).collect(),
+        named_results,
         statements: parsed_query.statements,
         variables: query_state.extract_required_variables(),
         state: query_state,
@@ -115,7 +163,7 @@ pub fn read_surql_files(dir_path: &str) -> io::Result<BTreeMap<String, String>>
         let entry = entry?;
         let file_path = entry.path();
 
-        if file_path.is_file() && file_path.extension().map_or(false, |ext| ext == "surql") {
+        if file_path.is_file() && file_path.extension().is_some_and(|ext| ext == "surql") {
             let file_name = file_path
                 .file_name()
                 .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid file name"))?
@@ -0,0 +1,165 @@
+use crate::{kind, utils::printing::indent, Kind};
+use surrealdb::sql::{Literal, Number};
+
+use crate::step_2_interpret::SchemaState;
+
+use super::{
+    format_key, format_number, geometry::geometry_fixture_value, get_record_id_value_type,
+    GeometryOutput, TypeData,
+};
+use crate::step_3_codegen::to_pascal_case;
+
+/// Generates a `fixtures.ts` file containing factory functions for every query result
+/// and every table's select shape, eg: `makeGetUserResult()` and `makeUser()`.
+///
+/// The returned values are deterministic, and always pick the first branch of a union.
+pub fn generate_typescript_fixtures(
+    types: &[TypeData],
+    schema: &SchemaState,
+    header: &str,
+    types_import_path: &str,
+    geometry: GeometryOutput,
+) -> Result<String, anyhow::Error> {
+    let mut output = String::new();
+
+    output.push_str(header);
+    output.push('\n');
+
+    let imports = types
+        .iter()
+        .map(|TypeData { name, .. }| format!("{}Result", name))
+        .chain(
+            schema
+                .schema
+                .tables
+                .keys()
+                .map(|table_name| format!("{}Record", to_pascal_case(table_name))),
+        )
+        .collect::<Vec<_>>();
+
+    if !imports.is_empty() {
+        output.push_str(&format!(
+            "import type {{ {} }} from {}\n",
+            imports.join(", "),
+            serde_json::to_string(types_import_path)?
+        ));
+    }
+
+    for TypeData {
+        name, return_type, ..
+    } in types
+    {
+        let mut values = Vec::new();
+        for result in return_type {
+            values.push(fixture_value(result, schema, "result", geometry)?);
+        }
+
+        output.push_str(&format!(
+            "\nexport function make{name}Result(overrides: Partial<{name}Result> = []): {name}Result {{\n    return Object.assign([\n{}\n    ], overrides) as {name}Result\n}}\n",
+            indent(&indent(&values.join(",\n"))),
+        ));
+    }
+
+    for (table_name, table) in &schema.schema.tables {
+        let name = to_pascal_case(table_name);
+        let fields = kind!(Obj table.compute_select_fields()?);
+
+        output.push_str(&format!(
+            "\nexport function make{name}(overrides: Partial<{name}Record> = {{}}): {name}Record {{\n    return {{\n{},\n        ...overrides,\n    }}\n}}\n",
+            indent(&indent(&object_fixture_fields(&fields, schema, geometry)?)),
+        ));
+    }
+
+    Ok(output)
+}
+
+fn object_fixture_fields(
+    kind: &Kind,
+    schema: &SchemaState,
+    geometry: GeometryOutput,
+) -> Result<String, anyhow::Error> {
+    match kind {
+        Kind::Literal(Literal::Object(map)) => {
+            let mut lines = Vec::new();
+            for (key, value) in map {
+                lines.push(format!(
+                    "{}: {}",
+                    format_key(key),
+                    fixture_value(value, schema, key, geometry)?
+                ));
+            }
+            Ok(lines.join(",\n"))
+        }
+        _ => anyhow::bail!("Expected an object type, got: {}", kind),
+    }
+}
+
+/// Generates a deterministic TypeScript value expression conforming to the given `Kind`.
+/// `key` is the name of the field the value is for, which is used as the fake string value.
+fn fixture_value(
+    kind: &Kind,
+    schema: &SchemaState,
+    key: &str,
+    geometry: GeometryOutput,
+) -> Result<String, anyhow::Error> {
+    Ok(match kind {
+        Kind::Any | Kind::Object => "{}".into(),
+        Kind::Null => "null".into(),
+        Kind::String => serde_json::to_string(key)?,
+        Kind::Int | Kind::Float | Kind::Number => "1".into(),
+        Kind::Decimal => "new Decimal(\"1\")".into(),
+        Kind::Datetime => "new Date(\"2024-01-01T00:00:00.000Z\")".into(),
+        Kind::Duration => "new Duration(\"1h\")".into(),
+        Kind::Bool => "true".into(),
+        Kind::Uuid => "\"00000000-0000-0000-0000-000000000000\"".into(),
+        Kind::Bytes => "new Uint8Array()".into(),
+        Kind::Point => geometry_fixture_value(&["point".into()], geometry)?,
+        Kind::Geometry(kinds) => geometry_fixture_value(kinds, geometry)?,
+        Kind::Record(tables) if tables.is_empty() => format!(
+            "new RecordId({}, {})",
+            serde_json::to_string(key)?,
+            serde_json::to_string(key)?
+        ),
+        Kind::Record(tables) => {
+            let table = tables.first().unwrap().0.as_str();
+            let id_value_type = get_record_id_value_type(table, schema)?;
+            format!(
+                "new RecordId({}, {})",
+                serde_json::to_string(table)?,
+                fixture_value(&id_value_type, schema, table, geometry)?
+            )
+        }
+        Kind::Option(inner) => fixture_value(inner, schema, key, geometry)?,
+        Kind::Either(kinds) => match kinds.first() {
+            Some(kind) => fixture_value(kind, schema, key, geometry)?,
+            None => anyhow::bail!("Cannot generate a fixture for an empty union"),
+        },
+        Kind::Array(inner, ..) => format!("[{}]", fixture_value(inner, schema, key, geometry)?),
+        Kind::Literal(Literal::String(string)) => serde_json::to_string(string.as_str())?,
+        Kind::Literal(Literal::Number(Number::Decimal(decimal))) => format!(
+            "new Decimal({})",
+            serde_json::to_string(&decimal.to_string())?
+        ),
+        Kind::Literal(Literal::Number(number)) => format_number(number),
+        Kind::Literal(Literal::Duration(duration)) => format!(
+            "new Duration({})",
+            serde_json::to_string(&duration.to_string())?
+        ),
+        Kind::Literal(Literal::Array(array)) => {
+            let mut values = Vec::new();
+            for value in array {
+                values.push(fixture_value(value, schema, key, geometry)?);
+            }
+            format!("[{}]", values.join(", "))
+        }
+        Kind::Literal(Literal::Object(map)) => match map.is_empty() {
+            true => "{}".into(),
+            false => format!(
+                "{{\n{}\n}}",
+                indent(&object_fixture_fields(kind, schema, geometry)?)
+            ),
+        },
+        // Catch all
+        kind => anyhow::bail!("Kind {:?} not yet supported for fixtures", kind),
+    })
+}
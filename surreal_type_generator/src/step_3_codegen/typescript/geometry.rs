@@ -0,0 +1,152 @@
+/// How `geometry<...>` values are represented in the generated TypeScript.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum GeometryOutput {
+    /// Plain GeoJSON objects, eg: `{ type: "Point", coordinates: [number, number] }`
+    #[default]
+    GeoJson,
+    /// The `surrealdb` SDK's `Geometry*` classes, eg: `GeometryPoint`, which must be imported in the header
+    Classes,
+}
+
+const GEOMETRY_KINDS: [&str; 7] = [
+    "point",
+    "line",
+    "polygon",
+    "multipoint",
+    "multiline",
+    "multipolygon",
+    "collection",
+];
+
+/// Returns the TypeScript type for `geometry<kinds>`, where no kinds means any geometry.
+pub(super) fn geometry_type_definition(
+    kinds: &[String],
+    output: GeometryOutput,
+) -> Result<String, anyhow::Error> {
+    let mut types = Vec::new();
+
+    for kind in geometry_kinds(kinds) {
+        types.push(match output {
+            GeometryOutput::GeoJson => geojson_type(kind)?,
+            GeometryOutput::Classes => class_name(kind)?.to_string(),
+        });
+    }
+
+    Ok(match types.len() {
+        1 => types.pop().unwrap(),
+        _ => format!("({})", types.join(" | ")),
+    })
+}
+
+/// Returns a TypeScript value for the first kind of `geometry<kinds>`, for use in fixtures.
+pub(super) fn geometry_fixture_value(
+    kinds: &[String],
+    output: GeometryOutput,
+) -> Result<String, anyhow::Error> {
+    let kind = geometry_kinds(kinds)[0];
+
+    Ok(match output {
+        GeometryOutput::GeoJson => match kind {
+            "collection" => format!(
+                "{{ type: \"GeometryCollection\", geometries: [{}] }}",
+                geometry_fixture_value(&["point".into()], output)?
+            ),
+            kind => format!(
+                "{{ type: \"{}\", coordinates: {} }}",
+                geojson_name(kind)?,
+                coordinates_fixture_value(kind)?
+            ),
+        },
+        GeometryOutput::Classes => {
+            let point = "new GeometryPoint([0, 0])";
+            let line = format!("new GeometryLine([{}, new GeometryPoint([1, 1])])", point);
+            let polygon = format!(
+                "new GeometryPolygon([new GeometryLine([{}, new GeometryPoint([1, 0]), new GeometryPoint([1, 1]), {}])])",
+                point, point
+            );
+            match kind {
+                "point" => point.to_string(),
+                "line" => line,
+                "polygon" => polygon,
+                "multipoint" => format!("new GeometryMultiPoint([{}])", point),
+                "multiline" => format!("new GeometryMultiLine([{}])", line),
+                "multipolygon" => format!("new GeometryMultiPolygon([{}])", polygon),
+                "collection" => format!("new GeometryCollection([{}])", point),
+                kind => anyhow::bail!("Unknown geometry type: {}", kind),
+            }
+        }
+    })
+}
+
+fn geometry_kinds(kinds: &[String]) -> Vec<&str> {
+    match kinds.is_empty() {
+        true => GEOMETRY_KINDS.to_vec(),
+        false => kinds.iter().map(String::as_str).collect(),
+    }
+}
+
+fn geojson_name(kind: &str) -> Result<&'static str, anyhow::Error> {
+    Ok(match kind {
+        "point" => "Point",
+        "line" => "LineString",
+        "polygon" => "Polygon",
+        "multipoint" => "MultiPoint",
+        "multiline" => "MultiLineString",
+        "multipolygon" => "MultiPolygon",
+        "collection" => "GeometryCollection",
+        kind => anyhow::bail!("Unknown geometry type: {}", kind),
+    })
+}
+
+fn geojson_type(kind: &str) -> Result<String, anyhow::Error> {
+    let position = "[number, number]";
+
+    let coordinates = match kind {
+        "point" => position.to_string(),
+        "line" | "multipoint" => format!("Array<{}>", position),
+        "polygon" | "multiline" => format!("Array<Array<{}>>", position),
+        "multipolygon" => format!("Array<Array<Array<{}>>>", position),
+        "collection" => {
+            let mut geometries = Vec::new();
+            for kind in &GEOMETRY_KINDS[..6] {
+                geometries.push(geojson_type(kind)?);
+            }
+            return Ok(format!(
+                "{{ type: \"GeometryCollection\", geometries: Array<{}> }}",
+                geometries.join(" | ")
+            ));
+        }
+        kind => anyhow::bail!("Unknown geometry type: {}", kind),
+    };
+
+    Ok(format!(
+        "{{ type: \"{}\", coordinates: {} }}",
+        geojson_name(kind)?,
+        coordinates
+    ))
+}
+
+fn coordinates_fixture_value(kind: &str) -> Result<&'static str, anyhow::Error> {
+    Ok(match kind {
+        "point" => "[0, 0]",
+        "line" => "[[0, 0], [1, 1]]",
+        "polygon" => "[[[0, 0], [1, 0], [1, 1], [0, 0]]]",
+        "multipoint" => "[[0, 0]]",
+        "multiline" => "[[[0, 0], [1, 1]]]",
+        "multipolygon" => "[[[[0, 0], [1, 0], [1, 1], [0, 0]]]]",
+        kind => anyhow::bail!("Unknown geometry type: {}", kind),
+    })
+}
+
+fn class_name(kind: &str) -> Result<&'static str, anyhow::Error> {
+    Ok(match kind {
+        "point" => "GeometryPoint",
+        "line" => "GeometryLine",
+        "polygon" => "GeometryPolygon",
+        "multipoint" => "GeometryMultiPoint",
+        "multiline" => "GeometryMultiLine",
+        "multipolygon" => "GeometryMultiPolygon",
+        "collection" => "GeometryCollection",
+        kind => anyhow::bail!("Unknown geometry type: {}", kind),
+    })
+}
@@ -1,11 +1,19 @@
+mod fixtures;
+mod geometry;
+mod template;
+
 use std::collections::HashSet;
 
 use crate::{kind, step_1_parse_sql::ViewParsed, utils::printing::indent, Kind, PrettyString};
-use surrealdb::sql::{Literal, Table};
+use surrealdb::sql::{Literal, Number, Table};
 
 use crate::step_2_interpret::SchemaState;
 
-use super::TypeData;
+use super::{to_camel_case, to_pascal_case, TypeData};
+
+pub use fixtures::generate_typescript_fixtures;
+pub use geometry::GeometryOutput;
+pub use template::{render_client_template, ClientTemplate};
 
 pub fn format_comment(string: &str) -> String {
     let mut lines = Vec::new();
@@ -20,6 +28,8 @@ pub fn format_comment(string: &str) -> String {
 pub fn generate_typescript_output(
     types: &[TypeData],
     header: &str,
+    client: &ClientTemplate,
+    geometry: GeometryOutput,
 ) -> Result<String, anyhow::Error> {
     let mut output = String::new();
 
@@ -34,7 +44,7 @@ pub fn generate_typescript_output(
             name, variables, ..
         } in types
         {
-            let has_variables = variables.len() > 0;
+            let has_variables = !variables.is_empty();
             output.push_str(&format!(
                 "    [{}Query]: {{variables: {}, result: {}Result }}\n",
                 name,
@@ -55,12 +65,13 @@ pub fn generate_typescript_output(
         statements,
         return_type,
         variables,
+        named_results,
     } in types
     {
         output.push_str(&format_comment(&format!(
             "## {} query results:\n\n```surql\n{}\n```",
             name,
-            &return_type
+            return_type
                 .iter()
                 .enumerate()
                 .map(|(i, x)| {
@@ -74,79 +85,124 @@ pub fn generate_typescript_output(
                 .collect::<Vec<_>>()
                 .join("\n\n"),
         )));
-        output.push_str("\n");
+        output.push('\n');
         output.push_str(&format!(
             "export const {}Query = {}\n",
             name,
             // Comment the query name so that they are distinguished between identical queries
-            serde_json::to_string(&format!("-- {}\n{}", name, &statements.to_string()))?
+            serde_json::to_string(&format!("-- {}\n{}", name, statements))?
         ));
         output.push_str(&format!("export type {}Result = [\n{}\n]\n", name, {
             let mut lines = Vec::new();
             for result in return_type {
-                lines.push(generate_type_definition(result, schema)?);
+                lines.push(generate_type_definition(result, schema, geometry)?);
             }
             indent(&lines.join(",\n"))
         }));
 
-        if variables.len() > 0 {
+        if !variables.is_empty() {
             output.push_str(&format!("export type {}Variables = ", name));
 
             output.push_str(&generate_type_definition(
                 &kind!(Obj variables.clone()),
                 schema,
+                geometry,
             )?);
 
-            output.push_str("\n");
+            output.push('\n');
         }
-    }
 
-    output.push_str(r#"
-
-export type Variables<Q extends keyof Queries> = Queries[Q]["variables"] extends never ? [] : [Queries[Q]["variables"]]
-
-/**
- * A Surreal client with typed queries from codegen.
- *
- * Usage:
- *
- * ```surql
- * // [your_schema_path].surql
- * DEFINE TABLE user SCHEMAFULL;
- * DEFINE FIELD name ON user TYPE string;
- * ```
- * ```surql
- * // queries/get_user.surql
- * SELECT * FROM ONLY $auth;
- * ```
- *
- * ```ts
- * // usage example
- * import { TypedSurreal, GetUserQuery } from "[YOUR_OUTPUT_PATH].ts"
- * const db = new TypedSurreal()
- *
- * await db.connect(...)
- *
- * const [
- *     user // { id: RecordId<"user">, name: string }
- * ] = await surreal.typed(GetUserQuery)
- *
- * console.log(user) // { id: 1, name: "John Doe" }
- * ```
- */
-export class TypedSurreal extends Surreal {
-    typed<Q extends keyof Queries>(query: Q, ...rest: Variables<Q>): Promise<Queries[Q]["result"]> {
-        return this.query(query, rest[0])
+        if !named_results.is_empty() {
+            output.push_str(&format!(
+                "export type {}NamedResult = {{\n{}\n}}\n",
+                name,
+                indent(
+                    &named_results
+                        .iter()
+                        .map(|(key, index)| format!("{}: {}Result[{}],", key, name, index))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            ));
+            output.push_str(&format!(
+                "export function {}NamedResult(result: {}Result): {}NamedResult {{\n    return {{\n{}\n    }}\n}}\n",
+                to_camel_case(name),
+                name,
+                name,
+                indent(&indent(
+                    &named_results
+                        .iter()
+                        .map(|(key, index)| format!("{}: result[{}],", key, index))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                ))
+            ));
+        }
     }
+
+    output.push_str(
+        "\n\nexport type Variables<Q extends keyof Queries> = Queries[Q][\"variables\"] extends never ? [] : [Queries[Q][\"variables\"]]\n",
+    );
+
+    output.push_str(&render_client_template(client.source(), types)?);
+
+    Ok(output)
 }
-"#);
+
+/// Generates a `[TableName]Record` type for the select shape of every table, eg: `UserRecord`,
+/// which is added to the output file when fixtures are generated, as the fixtures file imports them.
+pub fn generate_typescript_record_types(
+    state: &SchemaState,
+    geometry: GeometryOutput,
+) -> Result<String, anyhow::Error> {
+    let mut output = String::new();
+
+    for (table_name, table) in &state.schema.tables {
+        output.push_str(&format!(
+            "\nexport type {}Record = {}\n",
+            to_pascal_case(table_name),
+            generate_type_definition(&kind!(Obj table.compute_select_fields()?), state, geometry)?,
+        ));
+    }
 
     Ok(output)
 }
 
-fn get_table_id_type(table: &Table, schema: &SchemaState) -> Result<String, anyhow::Error> {
+/// Formats an object key, quoting it if it isn't a valid identifier, eg: `"first-name"`
+fn format_key(key: &str) -> String {
+    let mut chars = key.chars();
+
+    let is_identifier = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+    match is_identifier {
+        true => key.to_string(),
+        false => serde_json::Value::String(key.to_string()).to_string(),
+    }
+}
+
+/// Formats a number as a TypeScript number literal, eg: `1.5` rather than SurrealQL's `1.5f`
+fn format_number(number: &Number) -> String {
+    match number {
+        Number::Int(int) => int.to_string(),
+        number => match number.as_float() {
+            float if float.is_nan() => "NaN".into(),
+            float if float.is_infinite() && float > 0.0 => "Infinity".into(),
+            float if float.is_infinite() => "-Infinity".into(),
+            float => float.to_string(),
+        },
+    }
+}
+
+fn get_table_id_type(
+    table: &Table,
+    schema: &SchemaState,
+    geometry: GeometryOutput,
+) -> Result<String, anyhow::Error> {
     let record_id_type = get_record_id_value_type(table.0.as_str(), schema)?;
-    generate_type_definition(&record_id_type, schema)
+    generate_type_definition(&record_id_type, schema, geometry)
 }
 
 pub fn interpret_view_id_value_kind(
@@ -162,7 +218,7 @@ pub fn interpret_view_id_value_kind(
     match &view.groups {
         Some(_groups) => {
             // TODO: implement this
-            return Ok(Kind::Any);
+            Ok(Kind::Any)
         }
         None => get_record_id_value_type(&table_name, state),
     }
@@ -181,6 +237,7 @@ pub fn get_record_id_value_type(table: &str, schema: &SchemaState) -> Result<Kin
 fn generate_type_definition(
     return_type: &Kind,
     schema: &SchemaState,
+    geometry: GeometryOutput,
 ) -> Result<String, anyhow::Error> {
     match return_type {
         Kind::Any => Ok("any".to_string()),
@@ -194,28 +251,43 @@ fn generate_type_definition(
         Kind::Decimal => Ok("Decimal".to_string()),
         Kind::Bool => Ok("boolean".to_string()),
         Kind::Uuid => Ok("string".to_string()),
+        Kind::Bytes => Ok("Uint8Array".to_string()),
+        Kind::Point => geometry::geometry_type_definition(&["point".into()], geometry),
+        Kind::Geometry(kinds) => geometry::geometry_type_definition(kinds, geometry),
         Kind::Array(array, ..) => {
-            let string = generate_type_definition(&**array, schema)?;
+            let string = generate_type_definition(array, schema, geometry)?;
             Ok(format!("Array<{}>", string))
         }
         Kind::Either(vec) => {
-            let mut output = String::new();
-            output.push_str("(\n");
+            let mut members = Vec::new();
 
-            let mut lines = Vec::new();
+            for return_type in vec.iter() {
+                let member = generate_type_definition(return_type, schema, geometry)?;
+                // different kinds can have the same TypeScript type, eg: `int | float`
+                if !members.contains(&member) {
+                    members.push(member);
+                }
+            }
 
-            for return_type in vec.into_iter() {
-                lines.push(format!(
-                    "| {}",
-                    generate_type_definition(return_type, schema)?
-                ));
+            if members.len() == 1 {
+                return Ok(members.pop().unwrap());
             }
 
+            let mut output = String::new();
+            output.push_str("(\n");
+
+            let lines = members
+                .iter()
+                .map(|member| format!("| {}", member))
+                .collect::<Vec<_>>();
+
             output.push_str(&indent(&lines.join("\n")));
 
             output.push_str("\n)");
             Ok(output)
         }
+        // a record from any table, eg: `type::thing($table, $id)`
+        Kind::Record(tables) if tables.is_empty() => Ok("RecordId".to_string()),
         Kind::Record(tables) => {
             let mut output = String::new();
             output.push_str("(RecordId<");
@@ -229,12 +301,16 @@ fn generate_type_definition(
             output.push_str(&tables_joined);
 
             output.push_str("> & { id: ");
-            output.push_str(&get_table_id_type(tables.first().unwrap(), schema)?);
+            output.push_str(&get_table_id_type(
+                tables.first().unwrap(),
+                schema,
+                geometry,
+            )?);
             output.push_str(" })");
             Ok(output)
         }
         Kind::Option(optional_value) => {
-            let string = generate_type_definition(&**optional_value, schema)?;
+            let string = generate_type_definition(optional_value, schema, geometry)?;
             Ok(format!("{} | undefined", string))
         }
         Kind::Object => Ok("any".to_string()),
@@ -244,13 +320,15 @@ fn generate_type_definition(
         // ========
         Kind::Literal(Literal::String(string)) => Ok(serde_json::to_string(&string)?),
         Kind::Literal(Literal::Duration(_duration)) => Ok("Duration".to_string()),
-        Kind::Literal(Literal::Number(number)) => Ok(number.to_string()),
+        // decimals are `Decimal` instances, which have no literal type
+        Kind::Literal(Literal::Number(Number::Decimal(_))) => Ok("Decimal".to_string()),
+        Kind::Literal(Literal::Number(number)) => Ok(format_number(number)),
         Kind::Literal(Literal::Object(map)) => {
             let mut output = String::new();
             output.push_str("{\n");
 
             // sort alphabetically for deterministic output
-            let mut map: Vec<(_, _)> = map.into_iter().collect();
+            let mut map: Vec<(_, _)> = map.iter().collect();
             map.sort_by_key(|x| x.0.to_string());
 
             let mut key_string = Vec::new();
@@ -258,14 +336,14 @@ fn generate_type_definition(
             for (key, value) in map {
                 key_string.push(format!(
                     "{}{}: {},\n",
-                    key,
+                    format_key(key),
                     match value {
                         Kind::Option(_) => "?",
                         _ => "",
                     },
                     match value {
-                        Kind::Option(inner) => generate_type_definition(inner, schema)?,
-                        value => generate_type_definition(value, schema)?,
+                        Kind::Option(inner) => generate_type_definition(inner, schema, geometry)?,
+                        value => generate_type_definition(value, schema, geometry)?,
                     },
                 ));
             }
@@ -279,7 +357,7 @@ fn generate_type_definition(
         Kind::Literal(Literal::Array(array)) => {
             let mut array_types = HashSet::new();
             for value in array {
-                array_types.insert(generate_type_definition(value, schema)?);
+                array_types.insert(generate_type_definition(value, schema, geometry)?);
             }
 
             match array_types.len() {
@@ -0,0 +1,197 @@
+use super::{to_camel_case, TypeData};
+
+/// The client code emitted at the end of the generated file, after all of the query types.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ClientTemplate {
+    /// `class TypedSurreal extends Surreal` with a `.typed()` method
+    #[default]
+    Class,
+    /// `class TypedSurreal` which composes an existing `Surreal` instance
+    Wrapper,
+    /// A bare `function` per query which takes the `Surreal` instance as its first argument
+    Functions,
+    /// A user supplied template, see [`render_client_template`] for the supported placeholders
+    Custom(String),
+}
+
+impl ClientTemplate {
+    pub fn source(&self) -> &str {
+        match self {
+            ClientTemplate::Class => CLASS_TEMPLATE,
+            ClientTemplate::Wrapper => WRAPPER_TEMPLATE,
+            ClientTemplate::Functions => FUNCTIONS_TEMPLATE,
+            ClientTemplate::Custom(template) => template,
+        }
+    }
+}
+
+/// Renders a client template against the list of generated queries.
+///
+/// Supported placeholders:
+/// - `{{#each queries}} ... {{/each}}` repeats the inner text once per query
+/// - `{{name}}` the query name, eg: `GetUser`
+/// - `{{fn_name}}` the query name in camel case, eg: `getUser`
+/// - `{{query}}` the query constant, eg: `GetUserQuery`
+/// - `{{result}}` the result type, eg: `GetUserResult`
+/// - `{{variables}}` the variables type, eg: `GetUserVariables`, or `never` if there are none
+///
+/// A literal `{{` can be output by escaping it with a backslash, eg: `\{{`.
+pub fn render_client_template(template: &str, types: &[TypeData]) -> Result<String, anyhow::Error> {
+    let mut output = String::new();
+    let mut rest = template;
+
+    while let Some(start) = find_unescaped(rest, "{{#each queries}}") {
+        output.push_str(&render_placeholders(&rest[..start], None)?);
+        rest = &rest[start + "{{#each queries}}".len()..];
+
+        let end = match find_unescaped(rest, "{{/each}}") {
+            Some(end) => end,
+            None => anyhow::bail!("Unclosed `{{{{#each queries}}}}` block in client template"),
+        };
+
+        for type_data in types {
+            output.push_str(&render_placeholders(&rest[..end], Some(type_data))?);
+        }
+
+        rest = &rest[end + "{{/each}}".len()..];
+    }
+
+    output.push_str(&render_placeholders(rest, None)?);
+
+    Ok(output)
+}
+
+fn render_placeholders(text: &str, query: Option<&TypeData>) -> Result<String, anyhow::Error> {
+    let mut output = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        // `\{{` is a literal `{{`
+        if rest[..start].ends_with('\\') {
+            output.push_str(&rest[..start - 1]);
+            output.push_str("{{");
+            rest = &rest[start + 2..];
+            continue;
+        }
+
+        output.push_str(&rest[..start]);
+        rest = &rest[start + 2..];
+
+        let end = match rest.find("}}") {
+            Some(end) => end,
+            None => anyhow::bail!("Unclosed placeholder in client template"),
+        };
+
+        let placeholder = rest[..end].trim();
+
+        let query = match query {
+            Some(query) => query,
+            None => anyhow::bail!(
+                "Placeholder `{{{{{}}}}}` can only be used inside a `{{{{#each queries}}}}` block",
+                placeholder
+            ),
+        };
+
+        output.push_str(&match placeholder {
+            "name" => query.name.clone(),
+            "fn_name" => to_camel_case(&query.name),
+            "query" => format!("{}Query", query.name),
+            "result" => format!("{}Result", query.name),
+            "variables" => match query.variables.is_empty() {
+                true => "never".into(),
+                false => format!("{}Variables", query.name),
+            },
+            _ => anyhow::bail!(
+                "Unknown placeholder `{{{{{}}}}}` in client template",
+                placeholder
+            ),
+        });
+
+        rest = &rest[end + 2..];
+    }
+
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+/// Finds the first occurrence of `pattern` which isn't escaped with a backslash.
+fn find_unescaped(text: &str, pattern: &str) -> Option<usize> {
+    let mut offset = 0;
+
+    while let Some(start) = text[offset..].find(pattern) {
+        let start = offset + start;
+        if !text[..start].ends_with('\\') {
+            return Some(start);
+        }
+        offset = start + pattern.len();
+    }
+
+    None
+}
+
+const CLASS_TEMPLATE: &str = r#"
+/**
+ * A Surreal client with typed queries from codegen.
+ *
+ * Usage:
+ *
+ * ```surql
+ * // [your_schema_path].surql
+ * DEFINE TABLE user SCHEMAFULL;
+ * DEFINE FIELD name ON user TYPE string;
+ * ```
+ * ```surql
+ * // queries/get_user.surql
+ * SELECT * FROM ONLY $auth;
+ * ```
+ *
+ * ```ts
+ * // usage example
+ * import { TypedSurreal, GetUserQuery } from "[YOUR_OUTPUT_PATH].ts"
+ * const db = new TypedSurreal()
+ *
+ * await db.connect(...)
+ *
+ * const [
+ *     user // { id: RecordId<"user">, name: string }
+ * ] = await surreal.typed(GetUserQuery)
+ *
+ * console.log(user) // { id: 1, name: "John Doe" }
+ * ```
+ */
+export class TypedSurreal extends Surreal {
+    typed<Q extends keyof Queries>(query: Q, ...rest: Variables<Q>): Promise<Queries[Q]["result"]> {
+        return this.query(query, rest[0])
+    }
+}
+"#;
+
+const WRAPPER_TEMPLATE: &str = r#"
+/**
+ * Wraps an existing Surreal client with typed queries from codegen.
+ *
+ * ```ts
+ * // usage example
+ * import { TypedSurreal, GetUserQuery } from "[YOUR_OUTPUT_PATH].ts"
+ * const db = new TypedSurreal(surreal)
+ *
+ * const [
+ *     user // { id: RecordId<"user">, name: string }
+ * ] = await db.typed(GetUserQuery)
+ * ```
+ */
+export class TypedSurreal {
+    constructor(public readonly db: Surreal) {}
+
+    typed<Q extends keyof Queries>(query: Q, ...rest: Variables<Q>): Promise<Queries[Q]["result"]> {
+        return this.db.query(query, rest[0])
+    }
+}
+"#;
+
+const FUNCTIONS_TEMPLATE: &str = r#"{{#each queries}}
+export function {{fn_name}}(db: Surreal, ...rest: Variables<typeof {{query}}>): Promise<{{result}}> {
+    return db.query({{query}}, rest[0])
+}
+{{/each}}"#;
@@ -94,9 +94,13 @@ impl PrettyString for Kind {
 }
 
 pub fn indent(str: &str) -> String {
+    indent_with(str, "    ")
+}
+
+pub fn indent_with(str: &str, prefix: &str) -> String {
     let mut lines = Vec::new();
     for line in str.lines() {
-        lines.push(format!("    {}", line));
+        lines.push(format!("{}{}", prefix, line));
     }
     lines.join("\n")
 }
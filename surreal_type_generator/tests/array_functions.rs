@@ -0,0 +1,92 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, QueryResult};
+
+#[test]
+fn array_functions_infer_item_types() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    array::distinct(tags) AS distinct_tags,
+    array::first(tags) AS first_tag,
+    array::flatten(groups) AS flat_groups,
+    array::len(tags) AS tag_count,
+    array::at(scores, 1) AS second_score,
+    array::push(tags, 5) AS pushed,
+    array::windows(scores, 2) AS windows,
+    array::join(tags, ", ") AS joined
+FROM
+    post
+"#;
+    let schema = r#"
+DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD tags ON post TYPE array<string>;
+DEFINE FIELD groups ON post TYPE array<array<string>>;
+DEFINE FIELD scores ON post TYPE array<number>;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            distinct_tags: kind!([kind!(String)]),
+            first_tag: kind!(Opt(kind!(String))),
+            flat_groups: kind!([kind!(String)]),
+            tag_count: kind!(Number),
+            second_score: kind!(Opt(kind!(Number))),
+            pushed: kind!([kind!(Either[kind!(String), kind!(Number)])]),
+            windows: kind!([kind!([kind!(Number)])]),
+            joined: kind!(String)
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn array_functions_with_closures() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    array::map(tags, |$tag| $tag = "news") AS is_news,
+    array::filter(scores, |$score| $score > 10) AS high_scores,
+    array::find(scores, |$score| $score > 10) AS first_high_score,
+    array::fold(scores, "", |$acc, $score| $acc + "a") AS folded
+FROM
+    post
+"#;
+    let schema = r#"
+DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD tags ON post TYPE array<string>;
+DEFINE FIELD scores ON post TYPE array<number>;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            is_news: kind!([kind!(Bool)]),
+            high_scores: kind!([kind!(Number)]),
+            first_high_score: kind!(Opt(kind!(Number))),
+            folded: kind!(String)
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn array_functions_require_arrays() -> anyhow::Result<()> {
+    let query = r#"
+SELECT array::len(name) AS length FROM post
+"#;
+    let schema = r#"
+DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD name ON post TYPE string;
+"#;
+
+    assert!(surreal_type_generator::step_3_codegen::query_to_return_type(query, schema).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, Kind, Literal, QueryResult};
+
+#[test]
+fn object_functions_use_object_shape() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    object::keys(settings) AS keys,
+    object::values(settings) AS values,
+    object::entries(settings) AS entries,
+    object::len(settings) AS length
+FROM
+    user
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD settings ON user TYPE { theme: string, volume: int };
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            keys: kind!([kind!(Either[
+                Kind::Literal(Literal::String("theme".into())),
+                Kind::Literal(Literal::String("volume".into()))
+            ])]),
+            values: kind!([kind!(Either[kind!(String), kind!(Int)])]),
+            entries: kind!([kind!(Either[
+                Kind::Literal(Literal::Array(vec![
+                    Kind::Literal(Literal::String("theme".into())),
+                    kind!(String)
+                ])),
+                Kind::Literal(Literal::Array(vec![
+                    Kind::Literal(Literal::String("volume".into())),
+                    kind!(Int)
+                ]))
+            ])]),
+            length: kind!(Int)
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_encoding_rand_and_session_functions() -> anyhow::Result<()> {
+    let query = r#"
+RETURN {
+    host: parse::url::host("https://surrealdb.com"),
+    port: parse::url::port("https://surrealdb.com"),
+    user: parse::email::user("tobie@surrealdb.com"),
+    encoded: encoding::base64::encode("foo"),
+    id: rand::uuid(),
+    choice: rand::enum("a", 1),
+    db: session::db(),
+    ip: session::ip()
+};
+"#;
+    let schema = r#""#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!({
+            host: kind!(Opt(kind!(String))),
+            port: kind!(Opt(kind!(Int))),
+            user: kind!(Opt(kind!(String))),
+            encoded: kind!(String),
+            id: kind!(Uuid),
+            choice: kind!(Either[kind!(String), kind!(Number)]),
+            db: kind!(String),
+            ip: kind!(Opt(kind!(String)))
+        })]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn meta_id_returns_id_value_type() -> anyhow::Result<()> {
+    let query = r#"
+SELECT meta::id(id) AS user_id, record::id(id) AS record_id, meta::tb(id) AS table FROM user;
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD id ON user TYPE int;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            user_id: kind!(Int),
+            record_id: kind!(Int),
+            table: kind!(String)
+        })])]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{
+    step_2_interpret::interpret_schema,
+    step_3_codegen::{
+        generate_type_info,
+        typescript::{
+            generate_typescript_output, render_client_template, ClientTemplate, GeometryOutput,
+        },
+    },
+};
+
+#[test]
+fn functions_client_template() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+    let types = vec![generate_type_info(
+        "get_user.surql",
+        "SELECT * FROM user;",
+        state,
+    )?];
+
+    let output = generate_typescript_output(
+        &types,
+        "",
+        &ClientTemplate::Functions,
+        GeometryOutput::GeoJson,
+    )?;
+
+    assert!(output.contains(
+        "export function getUser(db: Surreal, ...rest: Variables<typeof GetUserQuery>): Promise<GetUserResult> {"
+    ));
+    assert!(!output.contains("export class TypedSurreal"));
+
+    Ok(())
+}
+
+#[test]
+fn custom_client_template() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+    let types = vec![
+        generate_type_info("get_user.surql", "SELECT * FROM user;", state.clone())?,
+        generate_type_info("create_user.surql", "CREATE user CONTENT $user;", state)?,
+    ];
+
+    let output = render_client_template(
+        "export const pool = {\n{{#each queries}}    {{fn_name}}: [{{query}}, {} as {{variables}}],\n{{/each}}}\n",
+        &types,
+    )?;
+
+    assert_eq_sorted!(
+        output,
+        "export const pool = {\n    getUser: [GetUserQuery, {} as never],\n    createUser: [CreateUserQuery, {} as CreateUserVariables],\n}\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn client_template_errors_on_invalid_placeholders() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+    let types = vec![generate_type_info(
+        "get_user.surql",
+        "SELECT * FROM user;",
+        state,
+    )?];
+
+    assert!(render_client_template("{{#each queries}}{{foo}}{{/each}}", &types).is_err());
+    assert!(render_client_template("{{name}}", &types).is_err());
+    assert!(render_client_template("{{#each queries}}{{name}}", &types).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn client_template_escapes_braces() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+    let types = vec![generate_type_info(
+        "get_user.surql",
+        "SELECT * FROM user;",
+        state,
+    )?];
+
+    let output = render_client_template(
+        "const style = \\{{ color: 'red' }}\n\\{{#each queries}}\n{{#each queries}}{{name}}: `\\{{{{query}}}}`\n{{/each}}",
+        &types,
+    )?;
+
+    assert_eq_sorted!(
+        output,
+        "const style = {{ color: 'red' }}\n{{#each queries}}\nGetUser: `{{GetUserQuery}}`\n"
+    );
+
+    Ok(())
+}
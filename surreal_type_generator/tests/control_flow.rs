@@ -0,0 +1,168 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+"#;
+
+#[test]
+fn for_loops_type_the_loop_parameter() -> anyhow::Result<()> {
+    let query = r#"
+<array<string>> $names;
+
+FOR $name IN $names {
+    IF string::len($name) < 3 {
+        CONTINUE;
+    };
+
+    IF $name = "stop" {
+        BREAK;
+    };
+
+    RETURN $name;
+};
+
+FOR $i IN 0..10 {
+    LET $double = $i * 2;
+    IF $double > 10 {
+        RETURN $i;
+    };
+};
+
+FOR $user IN (SELECT * FROM user) {
+    RETURN $user.name;
+};
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!(Opt(kind!(String))),
+            kind!(Opt(kind!(Int))),
+            kind!(Opt(kind!(String))),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn for_loops_iterate_over_optional_arrays() -> anyhow::Result<()> {
+    let query = r#"
+<option<array<string>>> $names;
+
+FOR $name IN $names {
+    RETURN string::uppercase($name);
+};
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(return_types, vec![kind!(Opt(kind!(String)))]);
+
+    Ok(())
+}
+
+#[test]
+fn function_bodies_called_in_a_loop_are_not_inside_the_loop() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE FUNCTION fn::stop() {
+    BREAK;
+};"#;
+
+    let query = r#"
+FOR $i IN 0..10 {
+    fn::stop();
+};
+"#;
+
+    match surreal_type_generator::step_3_codegen::query_to_return_type(query, schema) {
+        Ok(_) => panic!("Expected an error for: {}", query),
+        Err(err) => assert_eq_sorted!(
+            format!("{:#}", err),
+            "`BREAK` can only be used inside a `FOR` loop"
+        ),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn if_statements_without_else_which_return_are_optional() -> anyhow::Result<()> {
+    let query = r#"
+<option<string>> $name;
+
+IF $name != NONE {
+    RETURN string::uppercase($name);
+};
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(return_types, vec![kind!(Opt(kind!(String)))]);
+
+    Ok(())
+}
+
+#[test]
+fn throw_is_excluded_from_branch_unions() -> anyhow::Result<()> {
+    let query = r#"
+<option<string>> $name;
+
+IF $name = NONE { THROW "missing name" } ELSE { $name };
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(return_types, vec![kind!(String)]);
+
+    Ok(())
+}
+
+#[test]
+fn break_and_continue_must_be_inside_a_loop() -> anyhow::Result<()> {
+    for query in [
+        "BREAK;",
+        "CONTINUE;",
+        "IF true { BREAK; };",
+        "FOR $name IN 'not an array' { BREAK; };",
+    ] {
+        assert!(
+            surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA).is_err(),
+            "expected an error for: {}",
+            query
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn cancelled_transactions_have_no_results() -> anyhow::Result<()> {
+    let query = r#"
+BEGIN;
+SELECT * FROM user;
+CANCEL;
+
+SELECT name FROM user;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            name: kind!(String)
+        })])]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{
+    step_2_interpret::interpret_schema,
+    step_3_codegen::{
+        generate_type_info,
+        typescript::{
+            generate_typescript_fixtures, generate_typescript_record_types, GeometryOutput,
+        },
+    },
+};
+
+#[test]
+fn generates_fixtures_for_queries_and_tables() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD age ON user TYPE option<int>;
+DEFINE FIELD created_at ON user TYPE datetime;
+DEFINE FIELD role ON user TYPE "admin" | "user";
+DEFINE FIELD tags ON user TYPE array<string>;
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+    let types = vec![generate_type_info(
+        "get_user.surql",
+        "SELECT name, role FROM ONLY user:john;",
+        state.clone(),
+    )?];
+
+    let output = generate_typescript_fixtures(
+        &types,
+        &state,
+        "import { RecordId, Duration, Decimal } from 'surrealdb'",
+        "./types",
+        GeometryOutput::GeoJson,
+    )?;
+
+    assert_eq_sorted!(
+        output,
+        r#"import { RecordId, Duration, Decimal } from 'surrealdb'
+import type { GetUserResult, UserRecord } from "./types"
+
+export function makeGetUserResult(overrides: Partial<GetUserResult> = []): GetUserResult {
+    return Object.assign([
+        {
+            name: "name",
+            role: "admin"
+        }
+    ], overrides) as GetUserResult
+}
+
+export function makeUser(overrides: Partial<UserRecord> = {}): UserRecord {
+    return {
+        age: 1,
+        created_at: new Date("2024-01-01T00:00:00.000Z"),
+        id: new RecordId("user", "user"),
+        name: "name",
+        role: "admin",
+        tags: ["tags"],
+        ...overrides,
+    }
+}
+"#
+    );
+
+    // the record types are declared in the types file which the fixtures import from
+    let output = generate_typescript_record_types(&state, GeometryOutput::GeoJson)?;
+
+    assert!(
+        output.contains(
+            r#"
+export type UserRecord = {
+    age?: number,
+    created_at: Date,
+    id: (RecordId<"user"> & { id: string }),
+    name: string,
+    role: (
+        | "admin"
+        | "user"
+    ),
+    tags: Array<string>,
+}
+"#
+        ),
+        "{}",
+        output
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fixtures_format_number_literals_and_quote_keys() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE product SCHEMAFULL;
+DEFINE FIELD weight ON product TYPE 1.5f | 2.5f;
+DEFINE FIELD price ON product TYPE 9.99dec;
+DEFINE FIELD labels ON product TYPE { "first-name": string, last_name: string };
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+
+    let output = generate_typescript_fixtures(
+        &[],
+        &state,
+        "import { RecordId, Duration, Decimal } from 'surrealdb'",
+        "./types",
+        GeometryOutput::GeoJson,
+    )?;
+
+    assert_eq_sorted!(
+        output,
+        r#"import { RecordId, Duration, Decimal } from 'surrealdb'
+import type { ProductRecord } from "./types"
+
+export function makeProduct(overrides: Partial<ProductRecord> = {}): ProductRecord {
+    return {
+        id: new RecordId("product", "product"),
+        labels: {
+            "first-name": "first-name",
+            last_name: "last_name"
+        },
+        price: new Decimal("9.99"),
+        weight: 1.5,
+        ...overrides,
+    }
+}
+"#
+    );
+
+    assert_eq_sorted!(
+        generate_typescript_record_types(&state, GeometryOutput::GeoJson)?,
+        r#"
+export type ProductRecord = {
+    id: (RecordId<"product"> & { id: string }),
+    labels: {
+        "first-name": string,
+        last_name: string,
+    },
+    price: Decimal,
+    weight: (
+        | 1.5
+        | 2.5
+    ),
+}
+"#
+    );
+
+    Ok(())
+}
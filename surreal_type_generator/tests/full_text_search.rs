@@ -0,0 +1,64 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, var_map, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase,ascii;
+DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD title ON post TYPE string;
+DEFINE FIELD body ON post TYPE string;
+DEFINE INDEX body_search ON post FIELDS body SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+"#;
+
+#[test]
+fn matches_operator_infers_search_parameter() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    id,
+    search::score(1) AS score,
+    search::highlight("<b>", "</b>", 1) AS highlighted,
+    search::offsets(1) AS offsets
+FROM
+    post
+WHERE
+    body @1@ $query
+"#;
+
+    let QueryResult {
+        return_types,
+        variables,
+        ..
+    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            query: kind!(String)
+        }
+    );
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            id: kind!(Record["post"]),
+            score: kind!(Number),
+            highlighted: kind!(String),
+            offsets: kind!(Object)
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn matches_operator_requires_search_index() -> anyhow::Result<()> {
+    let query = r#"
+SELECT * FROM post WHERE title @@ $query
+"#;
+
+    match surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA) {
+        Ok(_) => anyhow::bail!("Expected an error for a field without a SEARCH index"),
+        Err(error) => assert!(error.to_string().contains("SEARCH"), "{}", error),
+    }
+
+    Ok(())
+}
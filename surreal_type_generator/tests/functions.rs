@@ -1,5 +1,4 @@
-use pretty_assertions_sorted;
-use surreal_type_generator::QueryResult;
+use surreal_type_generator::{kind, QueryResult};
 
 #[test]
 fn custom_function_return_types() -> anyhow::Result<()> {
@@ -22,3 +21,220 @@ RETURN fn::foo(9);
 
     Ok(())
 }
+
+#[test]
+fn custom_function_arguments_infer_parameters() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE FUNCTION fn::greet($name: string, $times: option<int>) {
+    RETURN "hello";
+};"#;
+
+    let query = r#"
+RETURN fn::greet($name);
+RETURN fn::greet("world", $times);
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    pretty_assertions_sorted::assert_eq_sorted!(
+        variables,
+        surreal_type_generator::var_map! {
+            name: surreal_type_generator::kind!(String),
+            times: surreal_type_generator::kind!(Opt(surreal_type_generator::kind!(Int))),
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn custom_function_arguments_are_validated() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE FUNCTION fn::greet($name: string, $times: option<int>) {
+    RETURN "hello";
+};"#;
+
+    for (query, error) in [
+        (
+            "RETURN fn::greet();",
+            "`fn::greet` expects 1 to 2 arguments, but got 0",
+        ),
+        (
+            "RETURN fn::greet('a', 1, 2);",
+            "`fn::greet` expects 1 to 2 arguments, but got 3",
+        ),
+        (
+            "RETURN fn::greet(5);",
+            "Argument 1 of `fn::greet` expects `string`, but got `number`",
+        ),
+        (
+            "RETURN fn::greet('a', 'b');",
+            "Argument 2 of `fn::greet` expects `option<int>`, but got `string`",
+        ),
+    ] {
+        match surreal_type_generator::step_3_codegen::query_to_return_type(query, schema) {
+            Ok(_) => panic!("Expected an error for: {}", query),
+            Err(err) => pretty_assertions_sorted::assert_eq_sorted!(err.to_string(), error),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn custom_function_declared_return_types() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE FUNCTION fn::factorial($n: int) -> int {
+    IF $n <= 1 {
+        RETURN 1;
+    };
+
+    RETURN $n * fn::factorial($n - 1);
+};"#;
+
+    let query = r#"
+RETURN fn::factorial(5);
+RETURN fn::factorial(10);
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    pretty_assertions_sorted::assert_eq_sorted!(
+        return_types,
+        vec![
+            surreal_type_generator::Kind::Int,
+            surreal_type_generator::Kind::Int
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn custom_function_bodies_are_checked_against_declared_return_types() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE FUNCTION fn::name($id: int) -> string {
+    LET $name = $missing;
+    RETURN $name;
+};
+
+DEFINE FUNCTION fn::count($items: array<string>) -> int {
+    IF array::len($items) > 10 {
+        RETURN "too many";
+    };
+
+    RETURN array::len($items);
+};"#;
+
+    for (query, error) in [
+        ("RETURN fn::name(1);", "Unknown parameter: $missing"),
+        (
+            "RETURN fn::count([]);",
+            "Function `fn::count` declares that it returns `int`, but its body returns `string | number`",
+        ),
+    ] {
+        match surreal_type_generator::step_3_codegen::query_to_return_type(query, schema) {
+            Ok(_) => panic!("Expected an error for: {}", query),
+            Err(err) => pretty_assertions_sorted::assert_eq_sorted!(err.to_string(), error),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn recursive_custom_functions_require_return_types() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE FUNCTION fn::forever($n: int) {
+    RETURN fn::forever($n + 1);
+};"#;
+
+    match surreal_type_generator::step_3_codegen::query_to_return_type(
+        "RETURN fn::forever(1);",
+        schema,
+    ) {
+        Ok(_) => panic!("Expected an error for a recursive function"),
+        Err(err) => pretty_assertions_sorted::assert_eq_sorted!(
+            err.to_string(),
+            "Recursive function `fn::forever` must declare its return type, eg: `DEFINE FUNCTION fn::forever(...) -> type { ... }`"
+        ),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn custom_function_bodies_are_interpreted_as_blocks() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD visits ON user TYPE int DEFAULT 0;
+
+DEFINE FUNCTION fn::visit($id: record<user>) {
+    LET $user = SELECT * FROM ONLY $id;
+
+    IF $user = NONE {
+        THROW "User not found";
+    };
+
+    FOR $tag IN ["a", "b"] {
+        UPSERT $id SET visits += 1;
+    };
+
+    IF $user.visits > 10 {
+        RETURN "regular";
+    };
+
+    $user.name;
+};"#;
+
+    let query = r#"
+RETURN fn::visit(user:foo);
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    pretty_assertions_sorted::assert_eq_sorted!(
+        return_types,
+        vec![surreal_type_generator::Kind::String]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn custom_function_return_paths_are_unioned() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE FUNCTION fn::describe($value: option<int>) {
+    IF $value = NONE {
+        RETURN NONE;
+    };
+
+    LET $doubled = $value * 2;
+
+    RETURN { value: $value, doubled: $doubled };
+};"#;
+
+    let query = r#"
+RETURN fn::describe(5);
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    pretty_assertions_sorted::assert_eq_sorted!(
+        return_types,
+        vec![kind!(Either[
+            kind!(Null),
+            kind!({
+                value: kind!(Int),
+                doubled: kind!(Number)
+            })
+        ])]
+    );
+
+    Ok(())
+}
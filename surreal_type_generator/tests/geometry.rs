@@ -0,0 +1,98 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{
+    kind,
+    step_2_interpret::interpret_schema,
+    step_3_codegen::{
+        generate_type_info,
+        typescript::{
+            generate_typescript_fixtures, generate_typescript_output, ClientTemplate,
+            GeometryOutput,
+        },
+    },
+    Kind, QueryResult,
+};
+
+#[test]
+fn geometry_fields_and_geo_functions() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    location,
+    area,
+    geo::distance(location, (-0.118092, 51.509865)) AS distance,
+    geo::centroid(area) AS center,
+    geo::hash::encode(location) AS hash
+FROM
+    store
+"#;
+    let schema = r#"
+DEFINE TABLE store SCHEMAFULL;
+DEFINE FIELD location ON store TYPE geometry<point>;
+DEFINE FIELD area ON store TYPE geometry<polygon|multipolygon>;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            location: Kind::Geometry(vec!["point".into()]),
+            area: Kind::Geometry(vec!["polygon".into(), "multipolygon".into()]),
+            distance: kind!(Number),
+            center: Kind::Geometry(vec!["point".into()]),
+            hash: kind!(String)
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn geometry_typescript_output() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE store SCHEMAFULL;
+DEFINE FIELD location ON store TYPE geometry<point>;
+DEFINE FIELD area ON store TYPE option<geometry<polygon|multipolygon>>;
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+    let types = vec![generate_type_info(
+        "get_stores.surql",
+        "SELECT location, area FROM store;",
+        state,
+    )?];
+
+    let geojson =
+        generate_typescript_output(&types, "", &ClientTemplate::Class, GeometryOutput::GeoJson)?;
+    assert!(geojson.contains(r#"location: { type: "Point", coordinates: [number, number] },"#));
+    assert!(geojson.contains(r#"area?: ({ type: "Polygon", coordinates: Array<Array<[number, number]>> } | { type: "MultiPolygon", coordinates: Array<Array<Array<[number, number]>>> }),"#));
+
+    let classes =
+        generate_typescript_output(&types, "", &ClientTemplate::Class, GeometryOutput::Classes)?;
+    assert!(classes.contains("location: GeometryPoint,"));
+    assert!(classes.contains("area?: (GeometryPolygon | GeometryMultiPolygon),"));
+
+    Ok(())
+}
+
+#[test]
+fn geometry_fixtures() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE store SCHEMAFULL;
+DEFINE FIELD location ON store TYPE geometry<point>;
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+
+    let geojson =
+        generate_typescript_fixtures(&[], &state, "", "./types", GeometryOutput::GeoJson)?;
+    assert!(geojson.contains(r#"location: { type: "Point", coordinates: [0, 0] },"#));
+
+    let classes =
+        generate_typescript_fixtures(&[], &state, "", "./types", GeometryOutput::Classes)?;
+    assert!(classes.contains("location: new GeometryPoint([0, 0]),"));
+
+    Ok(())
+}
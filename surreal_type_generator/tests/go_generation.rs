@@ -0,0 +1,529 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use surreal_type_generator::{
+    step_2_interpret::interpret_schema,
+    step_3_codegen::{generate_type_info, go::generate_go_output},
+};
+
+#[test]
+fn generates_go_structs_and_query_functions() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD age ON user TYPE option<int>;
+DEFINE FIELD created_at ON user TYPE datetime;
+DEFINE FIELD status ON user TYPE "active" | "banned";
+DEFINE FIELD payload ON user TYPE string | int;
+"#;
+
+    let query = r#"
+<record<user>> $user;
+
+-- @name user
+SELECT * FROM ONLY $user;
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+    let types = vec![generate_type_info("get_user.surql", query, state)?];
+
+    let output = generate_go_output(&types, "queries")?;
+
+    for expected in [
+        "package queries\n",
+        "type GetUserResult struct {\n\tUser GetUserUser\n}",
+        "type GetUserUser struct {\n\tAge *int64 `json:\"age,omitempty\"`\n\tCreatedAt models.CustomDateTime `json:\"created_at\"`\n\tID models.RecordID `json:\"id\"`\n\tName string `json:\"name\"`\n\tPayload GetUserUserPayload `json:\"payload\"`\n\tStatus string `json:\"status\"`\n}",
+        "type GetUserUserPayload struct {\n\tValue GetUserUserPayloadVariant\n}",
+        "type GetUserUserPayload0 string",
+        "type GetUserUserPayload1 int64",
+        "func (u GetUserUserPayload) MarshalCBOR() ([]byte, error) {\n\treturn (models.CborMarshaler{}).Marshal(u.Value)\n}",
+        "\tvar v1 GetUserUserPayload1\n\tif err := (models.CborUnmarshaler{}).Unmarshal(data, &v1); err == nil {",
+        "type GetUserVariables struct {\n\tUser models.RecordID `json:\"user\"`\n}",
+        "func GetUser(db *surrealdb.DB, variables GetUserVariables) (*GetUserResult, error) {",
+        "if result.User, err = decodeResult[GetUserUser]((*results)[0].Result); err != nil {",
+    ] {
+        assert!(output.contains(expected), "expected output to contain:\n{}", expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn generates_go_unions_and_queries() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD age ON user TYPE option<int>;
+
+DEFINE TABLE admin SCHEMAFULL;
+DEFINE FIELD name ON admin TYPE string;
+DEFINE FIELD level ON admin TYPE int;
+"#;
+
+    let query = r#"
+<{ kind: "click", x: int, y: int } | { kind: "key", key: string }> $event;
+<{ name: string } | { title: string, subtitle: option<string> }> $label;
+<string | int> $payload;
+
+-- @name result
+SELECT * FROM user, admin;
+
+-- @name variables
+RETURN $event;
+
+-- @name query
+RETURN $label;
+
+RETURN $payload;
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+    let types = vec![generate_type_info("get_user.surql", query, state)?];
+
+    let output = generate_go_output(&types, "queries")?;
+
+    pretty_assertions_sorted::assert_eq!(
+        output,
+        r#"// Code generated by surreal-codegen. DO NOT EDIT.
+
+package queries
+
+import (
+	"fmt"
+
+	surrealdb "github.com/surrealdb/surrealdb.go"
+	"github.com/surrealdb/surrealdb.go/pkg/models"
+)
+
+const GetUserQuery = "-- GetUser\nSELECT * FROM user, admin;\nRETURN $event;\nRETURN $label;\nRETURN $payload;"
+
+type GetUserResultItem0 struct {
+	Age *int64 `json:"age,omitempty"`
+	ID models.RecordID `json:"id"`
+	Name string `json:"name"`
+}
+
+func (GetUserResultItem0) isGetUserResultItemVariant() {}
+
+type GetUserResultItem1 struct {
+	ID models.RecordID `json:"id"`
+	Level int64 `json:"level"`
+	Name string `json:"name"`
+}
+
+func (GetUserResultItem1) isGetUserResultItemVariant() {}
+
+// GetUserResultItem is one of: GetUserResultItem0, GetUserResultItem1
+type GetUserResultItem struct {
+	Value GetUserResultItemVariant
+}
+
+type GetUserResultItemVariant interface {
+	isGetUserResultItemVariant()
+}
+
+// UnmarshalCBOR decodes the value into the variant which matches it
+func (u *GetUserResultItem) UnmarshalCBOR(data []byte) error {
+	var record struct {
+		ID models.RecordID `json:"id"`
+	}
+	if err := (models.CborUnmarshaler{}).Unmarshal(data, &record); err == nil {
+		switch record.ID.Table {
+		case "user":
+			var value GetUserResultItem0
+			if err := (models.CborUnmarshaler{}).Unmarshal(data, &value); err != nil {
+				return err
+			}
+			u.Value = value
+			return nil
+		case "admin":
+			var value GetUserResultItem1
+			if err := (models.CborUnmarshaler{}).Unmarshal(data, &value); err != nil {
+				return err
+			}
+			u.Value = value
+			return nil
+		}
+	}
+	return fmt.Errorf("value does not match any variant of GetUserResultItem")
+}
+
+// MarshalCBOR encodes the variant value, rather than the wrapper struct
+func (u GetUserResultItem) MarshalCBOR() ([]byte, error) {
+	return (models.CborMarshaler{}).Marshal(u.Value)
+}
+
+type GetUserVariables20 struct {
+	Kind string `json:"kind"`
+	X int64 `json:"x"`
+	Y int64 `json:"y"`
+}
+
+func (GetUserVariables20) isGetUserVariables2Variant() {}
+
+type GetUserVariables21 struct {
+	Key string `json:"key"`
+	Kind string `json:"kind"`
+}
+
+func (GetUserVariables21) isGetUserVariables2Variant() {}
+
+// GetUserVariables2 is one of: GetUserVariables20, GetUserVariables21
+type GetUserVariables2 struct {
+	Value GetUserVariables2Variant
+}
+
+type GetUserVariables2Variant interface {
+	isGetUserVariables2Variant()
+}
+
+// UnmarshalCBOR decodes the value into the variant which matches it
+func (u *GetUserVariables2) UnmarshalCBOR(data []byte) error {
+	var tag struct {
+		Tag string `json:"kind"`
+	}
+	if err := (models.CborUnmarshaler{}).Unmarshal(data, &tag); err == nil {
+		switch tag.Tag {
+		case "click":
+			var value GetUserVariables20
+			if err := (models.CborUnmarshaler{}).Unmarshal(data, &value); err != nil {
+				return err
+			}
+			u.Value = value
+			return nil
+		case "key":
+			var value GetUserVariables21
+			if err := (models.CborUnmarshaler{}).Unmarshal(data, &value); err != nil {
+				return err
+			}
+			u.Value = value
+			return nil
+		}
+	}
+	return fmt.Errorf("value does not match any variant of GetUserVariables2")
+}
+
+// MarshalCBOR encodes the variant value, rather than the wrapper struct
+func (u GetUserVariables2) MarshalCBOR() ([]byte, error) {
+	return (models.CborMarshaler{}).Marshal(u.Value)
+}
+
+type GetUserQuery20 struct {
+	Name string `json:"name"`
+}
+
+func (GetUserQuery20) isGetUserQuery2Variant() {}
+
+type GetUserQuery21 struct {
+	Subtitle *string `json:"subtitle,omitempty"`
+	Title string `json:"title"`
+}
+
+func (GetUserQuery21) isGetUserQuery2Variant() {}
+
+// GetUserQuery2 is one of: GetUserQuery20, GetUserQuery21
+type GetUserQuery2 struct {
+	Value GetUserQuery2Variant
+}
+
+type GetUserQuery2Variant interface {
+	isGetUserQuery2Variant()
+}
+
+// UnmarshalCBOR decodes the value into the variant which matches it
+func (u *GetUserQuery2) UnmarshalCBOR(data []byte) error {
+	var fields map[string]any
+	if err := (models.CborUnmarshaler{}).Unmarshal(data, &fields); err == nil {
+		if hasFields(fields, []string{"name"}, []string{"name"}) {
+			var value GetUserQuery20
+			if err := (models.CborUnmarshaler{}).Unmarshal(data, &value); err != nil {
+				return err
+			}
+			u.Value = value
+			return nil
+		}
+		if hasFields(fields, []string{"title"}, []string{"subtitle", "title"}) {
+			var value GetUserQuery21
+			if err := (models.CborUnmarshaler{}).Unmarshal(data, &value); err != nil {
+				return err
+			}
+			u.Value = value
+			return nil
+		}
+	}
+	return fmt.Errorf("value does not match any variant of GetUserQuery2")
+}
+
+// MarshalCBOR encodes the variant value, rather than the wrapper struct
+func (u GetUserQuery2) MarshalCBOR() ([]byte, error) {
+	return (models.CborMarshaler{}).Marshal(u.Value)
+}
+
+type GetUserResult30 string
+
+func (GetUserResult30) isGetUserResult3Variant() {}
+
+type GetUserResult31 int64
+
+func (GetUserResult31) isGetUserResult3Variant() {}
+
+// GetUserResult3 is one of: GetUserResult30, GetUserResult31
+type GetUserResult3 struct {
+	Value GetUserResult3Variant
+}
+
+type GetUserResult3Variant interface {
+	isGetUserResult3Variant()
+}
+
+// UnmarshalCBOR decodes the value into the variant which matches it
+func (u *GetUserResult3) UnmarshalCBOR(data []byte) error {
+	var v0 GetUserResult30
+	if err := (models.CborUnmarshaler{}).Unmarshal(data, &v0); err == nil {
+		u.Value = v0
+		return nil
+	}
+	var v1 GetUserResult31
+	if err := (models.CborUnmarshaler{}).Unmarshal(data, &v1); err == nil {
+		u.Value = v1
+		return nil
+	}
+	return fmt.Errorf("value does not match any variant of GetUserResult3")
+}
+
+// MarshalCBOR encodes the variant value, rather than the wrapper struct
+func (u GetUserResult3) MarshalCBOR() ([]byte, error) {
+	return (models.CborMarshaler{}).Marshal(u.Value)
+}
+
+type GetUserResult struct {
+	Result []GetUserResultItem
+	Variables GetUserVariables2
+	Query GetUserQuery2
+	Result3 GetUserResult3
+}
+
+type GetUserVariablesEvent0 struct {
+	Kind string `json:"kind"`
+	X int64 `json:"x"`
+	Y int64 `json:"y"`
+}
+
+func (GetUserVariablesEvent0) isGetUserVariablesEventVariant() {}
+
+type GetUserVariablesEvent1 struct {
+	Key string `json:"key"`
+	Kind string `json:"kind"`
+}
+
+func (GetUserVariablesEvent1) isGetUserVariablesEventVariant() {}
+
+// GetUserVariablesEvent is one of: GetUserVariablesEvent0, GetUserVariablesEvent1
+type GetUserVariablesEvent struct {
+	Value GetUserVariablesEventVariant
+}
+
+type GetUserVariablesEventVariant interface {
+	isGetUserVariablesEventVariant()
+}
+
+// UnmarshalCBOR decodes the value into the variant which matches it
+func (u *GetUserVariablesEvent) UnmarshalCBOR(data []byte) error {
+	var tag struct {
+		Tag string `json:"kind"`
+	}
+	if err := (models.CborUnmarshaler{}).Unmarshal(data, &tag); err == nil {
+		switch tag.Tag {
+		case "click":
+			var value GetUserVariablesEvent0
+			if err := (models.CborUnmarshaler{}).Unmarshal(data, &value); err != nil {
+				return err
+			}
+			u.Value = value
+			return nil
+		case "key":
+			var value GetUserVariablesEvent1
+			if err := (models.CborUnmarshaler{}).Unmarshal(data, &value); err != nil {
+				return err
+			}
+			u.Value = value
+			return nil
+		}
+	}
+	return fmt.Errorf("value does not match any variant of GetUserVariablesEvent")
+}
+
+// MarshalCBOR encodes the variant value, rather than the wrapper struct
+func (u GetUserVariablesEvent) MarshalCBOR() ([]byte, error) {
+	return (models.CborMarshaler{}).Marshal(u.Value)
+}
+
+type GetUserVariablesLabel0 struct {
+	Name string `json:"name"`
+}
+
+func (GetUserVariablesLabel0) isGetUserVariablesLabelVariant() {}
+
+type GetUserVariablesLabel1 struct {
+	Subtitle *string `json:"subtitle,omitempty"`
+	Title string `json:"title"`
+}
+
+func (GetUserVariablesLabel1) isGetUserVariablesLabelVariant() {}
+
+// GetUserVariablesLabel is one of: GetUserVariablesLabel0, GetUserVariablesLabel1
+type GetUserVariablesLabel struct {
+	Value GetUserVariablesLabelVariant
+}
+
+type GetUserVariablesLabelVariant interface {
+	isGetUserVariablesLabelVariant()
+}
+
+// UnmarshalCBOR decodes the value into the variant which matches it
+func (u *GetUserVariablesLabel) UnmarshalCBOR(data []byte) error {
+	var fields map[string]any
+	if err := (models.CborUnmarshaler{}).Unmarshal(data, &fields); err == nil {
+		if hasFields(fields, []string{"name"}, []string{"name"}) {
+			var value GetUserVariablesLabel0
+			if err := (models.CborUnmarshaler{}).Unmarshal(data, &value); err != nil {
+				return err
+			}
+			u.Value = value
+			return nil
+		}
+		if hasFields(fields, []string{"title"}, []string{"subtitle", "title"}) {
+			var value GetUserVariablesLabel1
+			if err := (models.CborUnmarshaler{}).Unmarshal(data, &value); err != nil {
+				return err
+			}
+			u.Value = value
+			return nil
+		}
+	}
+	return fmt.Errorf("value does not match any variant of GetUserVariablesLabel")
+}
+
+// MarshalCBOR encodes the variant value, rather than the wrapper struct
+func (u GetUserVariablesLabel) MarshalCBOR() ([]byte, error) {
+	return (models.CborMarshaler{}).Marshal(u.Value)
+}
+
+type GetUserVariablesPayload0 string
+
+func (GetUserVariablesPayload0) isGetUserVariablesPayloadVariant() {}
+
+type GetUserVariablesPayload1 int64
+
+func (GetUserVariablesPayload1) isGetUserVariablesPayloadVariant() {}
+
+// GetUserVariablesPayload is one of: GetUserVariablesPayload0, GetUserVariablesPayload1
+type GetUserVariablesPayload struct {
+	Value GetUserVariablesPayloadVariant
+}
+
+type GetUserVariablesPayloadVariant interface {
+	isGetUserVariablesPayloadVariant()
+}
+
+// UnmarshalCBOR decodes the value into the variant which matches it
+func (u *GetUserVariablesPayload) UnmarshalCBOR(data []byte) error {
+	var v0 GetUserVariablesPayload0
+	if err := (models.CborUnmarshaler{}).Unmarshal(data, &v0); err == nil {
+		u.Value = v0
+		return nil
+	}
+	var v1 GetUserVariablesPayload1
+	if err := (models.CborUnmarshaler{}).Unmarshal(data, &v1); err == nil {
+		u.Value = v1
+		return nil
+	}
+	return fmt.Errorf("value does not match any variant of GetUserVariablesPayload")
+}
+
+// MarshalCBOR encodes the variant value, rather than the wrapper struct
+func (u GetUserVariablesPayload) MarshalCBOR() ([]byte, error) {
+	return (models.CborMarshaler{}).Marshal(u.Value)
+}
+
+type GetUserVariables struct {
+	Event GetUserVariablesEvent `json:"event"`
+	Label GetUserVariablesLabel `json:"label"`
+	Payload GetUserVariablesPayload `json:"payload"`
+}
+
+func GetUser(db *surrealdb.DB, variables GetUserVariables) (*GetUserResult, error) {
+	vars := map[string]interface{}{}
+	vars["event"] = variables.Event
+	vars["label"] = variables.Label
+	vars["payload"] = variables.Payload
+	results, err := surrealdb.Query[any](db, GetUserQuery, vars)
+	if err != nil {
+		return nil, err
+	}
+	if len(*results) != 4 {
+		return nil, fmt.Errorf("expected 4 results, got %d", len(*results))
+	}
+	var result GetUserResult
+	if (*results)[0].Status != "OK" {
+		return nil, fmt.Errorf("result 0: %s: %v", (*results)[0].Status, (*results)[0].Result)
+	}
+	if result.Result, err = decodeResult[[]GetUserResultItem]((*results)[0].Result); err != nil {
+		return nil, err
+	}
+	if (*results)[1].Status != "OK" {
+		return nil, fmt.Errorf("result 1: %s: %v", (*results)[1].Status, (*results)[1].Result)
+	}
+	if result.Variables, err = decodeResult[GetUserVariables2]((*results)[1].Result); err != nil {
+		return nil, err
+	}
+	if (*results)[2].Status != "OK" {
+		return nil, fmt.Errorf("result 2: %s: %v", (*results)[2].Status, (*results)[2].Result)
+	}
+	if result.Query, err = decodeResult[GetUserQuery2]((*results)[2].Result); err != nil {
+		return nil, err
+	}
+	if (*results)[3].Status != "OK" {
+		return nil, fmt.Errorf("result 3: %s: %v", (*results)[3].Status, (*results)[3].Result)
+	}
+	if result.Result3, err = decodeResult[GetUserResult3]((*results)[3].Result); err != nil {
+		return nil, err
+	}
+	return &result, nil
+}
+
+func decodeResult[T any](value any) (T, error) {
+	var result T
+	data, err := (models.CborMarshaler{}).Marshal(value)
+	if err != nil {
+		return result, err
+	}
+	err = (models.CborUnmarshaler{}).Unmarshal(data, &result)
+	return result, err
+}
+
+// hasFields returns whether an object has every required field, and no fields which aren't known
+func hasFields(object map[string]any, required []string, known []string) bool {
+	for _, field := range required {
+		if _, ok := object[field]; !ok {
+			return false
+		}
+	}
+	for field := range object {
+		found := false
+		for _, key := range known {
+			if key == field {
+				found = true
+				break
+			}
+		}
+		if !found {
+			return false
+		}
+	}
+	return true
+}
+"#
+    );
+
+    Ok(())
+}
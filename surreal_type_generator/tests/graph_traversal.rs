@@ -1,4 +1,4 @@
-use surreal_type_generator::{kind, var_map, Kind, Literal, QueryResult};
+use surreal_type_generator::{kind, var_map, QueryResult};
 
 #[test]
 fn graph_traversal() -> anyhow::Result<()> {
@@ -20,7 +20,7 @@ fn graph_traversal() -> anyhow::Result<()> {
     "#;
 
     let QueryResult {
-        statements,
+        statements: _,
         return_types,
         ..
     } = surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
@@ -89,7 +89,7 @@ fn graph_traversal_with_auth_and_subquery() -> anyhow::Result<()> {
     }
 
     let QueryResult {
-        statements,
+        statements: _,
         return_types,
         ..
     } = result?;
@@ -0,0 +1,209 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{
+    step_2_interpret::interpret_schema,
+    step_3_codegen::{generate_type_info, graphql::generate_graphql_schema},
+};
+
+#[test]
+fn generates_graphql_schema() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD age ON user TYPE option<int>;
+DEFINE FIELD created_at ON user TYPE datetime;
+
+DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD title ON post TYPE string;
+DEFINE FIELD author ON post TYPE record<user>;
+DEFINE FIELD tags ON post TYPE array<string>;
+
+DEFINE TABLE post_titles AS SELECT title FROM post;
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+    let types = vec![
+        generate_type_info(
+            "get_posts.surql",
+            "<record<user>> $author;\nSELECT * FROM post WHERE author = $author;",
+            state.clone(),
+        )?,
+        generate_type_info(
+            "create_user.surql",
+            "CREATE user CONTENT $user;",
+            state.clone(),
+        )?,
+    ];
+
+    let output = generate_graphql_schema(&types, state)?;
+
+    assert_eq_sorted!(
+        output,
+        r#"scalar DateTime
+scalar Int64
+
+input CreateUserUserInput {
+    age: Int64
+    created_at: DateTime!
+    id: ID
+    name: String!
+}
+
+type Post {
+    author: User!
+    id: ID!
+    tags: [String!]!
+    title: String!
+}
+
+type PostTitles {
+    id: ID!
+    title: String!
+}
+
+type User {
+    age: Int64
+    created_at: DateTime!
+    id: ID!
+    name: String!
+}
+
+type Query {
+    getPosts(author: ID!): [Post!]!
+}
+
+type Mutation {
+    createUser(user: [CreateUserUserInput!]!): [User!]!
+}
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn nested_writes_are_mutations() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+    let types = vec![
+        generate_type_info(
+            "let_create.surql",
+            "LET $user = CREATE ONLY user CONTENT { name: 'John' };\nRETURN $user.name;",
+            state.clone(),
+        )?,
+        generate_type_info(
+            "if_update.surql",
+            "<string> $name;\nIF $name = 'John' { UPDATE user SET name = $name; };\nRETURN $name;",
+            state.clone(),
+        )?,
+        generate_type_info(
+            "return_delete.surql",
+            "RETURN (DELETE user RETURN BEFORE);",
+            state.clone(),
+        )?,
+        generate_type_info(
+            "select_names.surql",
+            "SELECT VALUE name FROM user;",
+            state.clone(),
+        )?,
+    ];
+
+    let output = generate_graphql_schema(&types, state)?;
+
+    let query = &output[output.find("type Query").unwrap()..output.find("type Mutation").unwrap()];
+    let mutation = &output[output.find("type Mutation").unwrap()..];
+
+    assert!(query.contains("selectNames"), "{}", output);
+    for name in ["letCreate", "ifUpdate", "returnDelete"] {
+        assert!(mutation.contains(name), "{}", output);
+        assert!(!query.contains(name), "{}", output);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn unions_are_flattened_and_geometry_and_bytes_are_scalars() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+
+DEFINE TABLE team SCHEMAFULL;
+DEFINE FIELD name ON team TYPE string;
+
+DEFINE TABLE bot SCHEMAFULL;
+DEFINE FIELD name ON bot TYPE string;
+
+DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD owner ON post TYPE record<user | team> | record<bot>;
+DEFINE FIELD location ON post TYPE geometry<point>;
+DEFINE FIELD area ON post TYPE option<geometry<polygon>>;
+DEFINE FIELD attachment ON post TYPE bytes;
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+
+    let output = generate_graphql_schema(&[], state)?;
+
+    assert_eq_sorted!(
+        output,
+        r#"scalar Bytes
+scalar Geometry
+
+type Bot {
+    id: ID!
+    name: String!
+}
+
+type Post {
+    area: Geometry
+    attachment: Bytes!
+    id: ID!
+    location: Geometry!
+    owner: PostOwner!
+}
+
+union PostOwner = User | Team | Bot
+
+type Team {
+    id: ID!
+    name: String!
+}
+
+type User {
+    id: ID!
+    name: String!
+}
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn conflicting_type_names_are_an_error() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE TABLE team SCHEMAFULL;
+DEFINE TABLE user_or_team SCHEMAFULL;
+
+DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD owner ON post TYPE record<user | team>;
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+
+    let error = generate_graphql_schema(&[], state).unwrap_err();
+
+    assert_eq_sorted!(
+        error.to_string(),
+        "GraphQL type name `UserOrTeam` is generated for more than one type"
+    );
+
+    Ok(())
+}
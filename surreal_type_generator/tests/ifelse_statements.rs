@@ -0,0 +1,115 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, Kind, QueryResult};
+
+#[test]
+fn ifelse_returns_union_of_branches() -> anyhow::Result<()> {
+    let query = r#"
+<bool> $flag;
+
+IF $flag { "yes" } ELSE { 1 };
+IF $flag THEN "yes" END;
+IF $flag { "yes" } ELSE IF !$flag { "no" } ELSE { NONE };
+"#;
+    let schema = r#"
+DEFINE TABLE placeholder SCHEMAFULL;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!(Either[kind!(String), kind!(Number)]),
+            kind!(Opt(kind!(String))),
+            kind!(Either[kind!(String), kind!(Null)]),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn ifelse_branches_with_statements() -> anyhow::Result<()> {
+    let query = r#"
+<record<user>> $user;
+
+LET $found = IF $user {
+    SELECT * FROM ONLY $user
+} ELSE {
+    NONE
+};
+
+RETURN $found;
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!(Null),
+            kind!(Either[
+                kind!({
+                    id: kind!(Record["user"]),
+                    name: kind!(String)
+                }),
+                kind!(Null)
+            ]),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn return_inside_branch_flows_to_statement_result() -> anyhow::Result<()> {
+    let query = r#"
+<bool> $flag;
+
+IF $flag {
+    RETURN "early";
+} ELSE {
+    LET $count = 1;
+    $count
+};
+"#;
+    let schema = r#"
+DEFINE TABLE placeholder SCHEMAFULL;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![Kind::Either(vec![Kind::Number, Kind::String])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn branch_variables_are_scoped_to_the_branch() -> anyhow::Result<()> {
+    let query = r#"
+<bool> $flag;
+
+IF $flag {
+    LET $count = 1;
+};
+
+RETURN $count;
+"#;
+    let schema = r#"
+DEFINE TABLE placeholder SCHEMAFULL;
+"#;
+
+    assert!(surreal_type_generator::step_3_codegen::query_to_return_type(query, schema).is_err());
+
+    Ok(())
+}
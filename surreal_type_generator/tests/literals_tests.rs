@@ -1,4 +1,3 @@
-use pretty_assertions_sorted;
 use surreal_type_generator::{kind, Kind, Literal, QueryResult};
 use surrealdb::sql::Duration;
 
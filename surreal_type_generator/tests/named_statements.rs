@@ -0,0 +1,163 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{
+    kind,
+    step_2_interpret::interpret_schema,
+    step_3_codegen::{
+        generate_type_info,
+        typescript::{generate_typescript_output, ClientTemplate, GeometryOutput},
+    },
+    QueryResult,
+};
+
+#[test]
+fn named_statements_are_resolved_to_result_indexes() -> anyhow::Result<()> {
+    let query = r#"
+<string> $name;
+
+LET $users = SELECT * FROM user WHERE name = $name;
+
+-- @name users
+SELECT name FROM user;
+
+-- @name total
+RETURN count($users);
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+"#;
+
+    let QueryResult {
+        return_types,
+        named_results,
+        ..
+    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!(Null),
+            kind!([kind!({ name: kind!(String) })]),
+            kind!(Number)
+        ]
+    );
+
+    assert_eq_sorted!(
+        named_results,
+        vec![("users".to_string(), 1), ("total".to_string(), 2)]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn named_statements_generate_typescript_helper() -> anyhow::Result<()> {
+    let query = r#"
+LET $foo = 1;
+-- @name users
+SELECT name FROM user;
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+    let types = vec![generate_type_info("get_users.surql", query, state)?];
+
+    let output =
+        generate_typescript_output(&types, "", &ClientTemplate::Class, GeometryOutput::GeoJson)?;
+
+    assert!(output.contains(
+        "export type GetUsersNamedResult = {\n    users: GetUsersResult[1],\n}\n\
+         export function getUsersNamedResult(result: GetUsersResult): GetUsersNamedResult {\n    return {\n        users: result[1],\n    }\n}\n"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn invalid_statement_names() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+"#;
+
+    for query in [
+        "-- @name users\nSELECT * FROM user;\n-- @name users\nSELECT * FROM user;",
+        "SELECT * FROM user;\n-- @name users",
+        "-- @name 1users\nSELECT * FROM user;",
+    ] {
+        assert!(
+            surreal_type_generator::step_3_codegen::query_to_return_type(query, schema).is_err()
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn name_comments_inside_strings_and_other_comments_are_ignored() -> anyhow::Result<()> {
+    let query = r#"
+-- @names are only read from `-- @name` comments
+/* -- @name ignored */
+LET $text = "
+-- @name not_a_name
+";
+
+-- @name text
+RETURN $text;
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+"#;
+
+    let QueryResult { named_results, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(named_results, vec![("text".to_string(), 1)]);
+
+    Ok(())
+}
+
+#[test]
+fn double_dashes_inside_statements_are_not_name_comments() -> anyhow::Result<()> {
+    let query = r#"
+LET $count = 1;
+-- @name count
+RETURN $count--@name not_a_name
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+"#;
+
+    let QueryResult { named_results, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(named_results, vec![("count".to_string(), 1)]);
+
+    Ok(())
+}
+
+#[test]
+fn name_comments_must_be_between_statements() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+"#;
+
+    for query in [
+        "SELECT *\n-- @name users\nFROM user;",
+        "IF true {\n-- @name users\nSELECT * FROM user;\n};",
+    ] {
+        match surreal_type_generator::step_3_codegen::query_to_return_type(query, schema) {
+            Ok(_) => panic!("Expected an error for: {}", query),
+            Err(err) => assert_eq_sorted!(
+                err.to_string(),
+                "`-- @name users` comments must be placed between statements"
+            ),
+        }
+    }
+
+    Ok(())
+}
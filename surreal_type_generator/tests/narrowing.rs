@@ -0,0 +1,116 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE TABLE placeholder SCHEMAFULL;
+"#;
+
+#[test]
+fn none_checks_narrow_options() -> anyhow::Result<()> {
+    let query = r#"
+<option<string>> $name;
+
+IF $name != NONE { $name } ELSE { "anonymous" };
+IF $name IS NONE { "anonymous" } ELSE { $name };
+IF $name { $name } ELSE { "anonymous" };
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!(String), kind!(String), kind!(String)]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn none_and_null_checks_narrow_separately() -> anyhow::Result<()> {
+    let query = r#"
+<option<string | null>> $name;
+
+IF $name != NONE { $name } ELSE { "anonymous" };
+IF $name != NULL { $name } ELSE { "anonymous" };
+IF type::is::none($name) { "anonymous" } ELSE { $name };
+IF type::is::null($name) { "anonymous" } ELSE { $name };
+IF $name { $name } ELSE { "anonymous" };
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!(Either[kind!(String), kind!(Null)]),
+            kind!(Opt(kind!(String))),
+            kind!(Either[kind!(String), kind!(Null)]),
+            kind!(Opt(kind!(String))),
+            kind!(String),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn type_checks_narrow_unions() -> anyhow::Result<()> {
+    let query = r#"
+<string | int> $value;
+
+IF type::is::string($value) { string::len($value) } ELSE { $value };
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(return_types, vec![kind!(Int)]);
+
+    Ok(())
+}
+
+#[test]
+fn literal_fields_narrow_discriminated_unions() -> anyhow::Result<()> {
+    let query = r#"
+<{ kind: "admin", permissions: array<string> } | { kind: "user", name: string }> $account;
+
+IF $account.kind = "admin" { $account.permissions } ELSE { $account.name };
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!(Either[kind!([kind!(String)]), kind!(String)])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn early_returns_narrow_the_rest_of_the_block() -> anyhow::Result<()> {
+    let query = r#"
+<bool> $flag;
+<option<string>> $name;
+
+IF $flag {
+    IF $name = NONE {
+        RETURN "anonymous";
+    };
+
+    $name
+} ELSE {
+    "disabled"
+};
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(return_types, vec![kind!(String)]);
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, var_map, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD age ON user TYPE int DEFAULT 18;
+DEFINE FIELD address ON user;
+DEFINE FIELD address.city ON user TYPE string;
+DEFINE FIELD address.zip ON user TYPE option<string>;
+DEFINE FIELD meta ON user TYPE option<{ source: string, score: float }>;
+DEFINE FIELD created_at ON user TYPE datetime READONLY;
+
+DEFINE TABLE log SCHEMALESS;
+DEFINE FIELD message ON log TYPE string;
+"#;
+
+#[test]
+fn create_content_object_infers_parameters() -> anyhow::Result<()> {
+    let query = r#"
+CREATE user CONTENT {
+    name: $name,
+    age: $age,
+    address: {
+        city: $city,
+        zip: $zip,
+    },
+    meta: {
+        source: $source,
+        score: 1.0,
+    },
+    created_at: time::now(),
+};
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            name: kind!(String),
+            age: kind!(Opt(kind!(Int))),
+            city: kind!(String),
+            zip: kind!(Opt(kind!(String))),
+            source: kind!(String),
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn update_and_upsert_merge_objects_infer_parameters() -> anyhow::Result<()> {
+    let query = r#"
+UPDATE user MERGE { name: $name, age: $age };
+UPSERT user MERGE { address: { zip: $zip } };
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            name: kind!(String),
+            age: kind!(Int),
+            zip: kind!(Opt(kind!(String))),
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn insert_objects_infer_parameters() -> anyhow::Result<()> {
+    let query = r#"
+INSERT INTO user { name: $name, created_at: $created_at };
+INSERT INTO log [{ message: $message, level: "info" }];
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            name: kind!(String),
+            created_at: kind!(Datetime),
+            message: kind!(String),
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn object_data_reports_invalid_fields() -> anyhow::Result<()> {
+    for (query, error) in [
+        (
+            "CREATE user CONTENT { name: $name, nickname: $nickname };",
+            "Field `nickname` does not exist on SCHEMAFULL table `user`",
+        ),
+        (
+            "CREATE user CONTENT { address: { country: $country } };",
+            "Field `address.country` does not exist on SCHEMAFULL table `user`",
+        ),
+        (
+            "UPDATE user MERGE { created_at: time::now() };",
+            "Field `created_at` is `READONLY` and cannot be updated",
+        ),
+    ] {
+        match surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA) {
+            Ok(_) => panic!("Expected an error for: {}", query),
+            Err(err) => assert_eq_sorted!(err.to_string(), error),
+        }
+    }
+
+    Ok(())
+}
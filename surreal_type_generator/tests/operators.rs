@@ -0,0 +1,136 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE TABLE product SCHEMAFULL;
+DEFINE FIELD name ON product TYPE string;
+DEFINE FIELD stock ON product TYPE int;
+DEFINE FIELD price ON product TYPE float;
+DEFINE FIELD tax ON product TYPE decimal;
+DEFINE FIELD discount ON product TYPE option<int>;
+DEFINE FIELD tags ON product TYPE array<string>;
+DEFINE FIELD created_at ON product TYPE datetime;
+DEFINE FIELD updated_at ON product TYPE datetime;
+DEFINE FIELD shelf_life ON product TYPE duration;
+"#;
+
+#[test]
+fn arithmetic_operators_widen_numbers() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    stock + stock AS int_sum,
+    stock * price AS float_product,
+    price - tax AS decimal_difference,
+    stock / stock AS quotient,
+    stock ** stock AS int_power,
+    price % stock AS remainder,
+    -stock AS negated,
+    stock - discount AS optional_difference,
+    stock + 1 AS number_sum
+FROM
+    product
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            int_sum: kind!(Int),
+            float_product: kind!(Float),
+            decimal_difference: kind!(Decimal),
+            quotient: kind!(Number),
+            int_power: kind!(Int),
+            remainder: kind!(Float),
+            negated: kind!(Int),
+            optional_difference: kind!(Int),
+            number_sum: kind!(Number)
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn arithmetic_operators_on_strings_and_datetimes() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    name + "!" AS title,
+    created_at + shelf_life AS expires_at,
+    created_at - 1d AS day_before,
+    updated_at - created_at AS age,
+    shelf_life + shelf_life AS double_shelf_life
+FROM
+    product
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            title: kind!(String),
+            expires_at: kind!(Datetime),
+            day_before: kind!(Datetime),
+            age: kind!(Duration),
+            double_shelf_life: kind!(Duration)
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn membership_and_fuzzy_operators_return_bool() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    tags CONTAINS "sale" AS on_sale,
+    tags CONTAINSANY ["new", "sale"] AS is_featured,
+    "sale" IN tags AS in_sale,
+    "sale" NOT IN tags AS not_in_sale,
+    tags ALLINSIDE ["new", "sale"] AS only_featured,
+    name ~ "shoe" AS is_shoe,
+    name !~ "shoe" AS is_not_shoe,
+    tags ?= "sale" AS any_sale
+FROM
+    product
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            on_sale: kind!(Bool),
+            is_featured: kind!(Bool),
+            in_sale: kind!(Bool),
+            not_in_sale: kind!(Bool),
+            only_featured: kind!(Bool),
+            is_shoe: kind!(Bool),
+            is_not_shoe: kind!(Bool),
+            any_sale: kind!(Bool)
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn invalid_arithmetic_operators_error() -> anyhow::Result<()> {
+    for query in [
+        "SELECT name - name AS invalid FROM product",
+        "SELECT tags + tags AS invalid FROM product",
+        "SELECT -name AS invalid FROM product",
+    ] {
+        assert!(
+            surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA).is_err(),
+            "expected an error for: {}",
+            query
+        );
+    }
+
+    Ok(())
+}
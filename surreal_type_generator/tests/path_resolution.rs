@@ -0,0 +1,66 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD best_friend ON user TYPE option<record<user>>;
+DEFINE FIELD address ON user;
+DEFINE FIELD address.city ON user TYPE string;
+DEFINE FIELD pets ON user TYPE array<{ name: string, age: int }>;
+"#;
+
+#[test]
+fn parameter_paths_resolve_nested_fields() -> anyhow::Result<()> {
+    let query = r#"
+<{ permissions: { role: string, scopes: array<string> } }> $account;
+
+RETURN $account.permissions;
+RETURN $account.permissions.role;
+RETURN $account.permissions.scopes[0];
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!({
+                role: kind!(String),
+                scopes: kind!([kind!(String)])
+            }),
+            kind!(String),
+            kind!(Opt(kind!(String))),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn field_paths_resolve_objects_records_and_arrays() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    address.city AS city,
+    best_friend.name AS best_friend_name,
+    pets.name AS pet_names,
+    pets[*].age AS pet_ages
+FROM ONLY user:foo;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!({
+            city: kind!(String),
+            best_friend_name: kind!(Opt(kind!(String))),
+            pet_names: kind!([kind!(String)]),
+            pet_ages: kind!([kind!(Int)])
+        })]
+    );
+
+    Ok(())
+}
@@ -124,11 +124,8 @@ fn relate_with_variable_ids() -> anyhow::Result<()> {
             TYPE RELATION;
     "#;
 
-    let QueryResult {
-        return_types,
-        variables,
-        ..
-    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
 
     let expected = vec![kind!([kind!({
         id: kind!(Record["workspaceOrg"]),
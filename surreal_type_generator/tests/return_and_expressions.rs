@@ -1,5 +1,15 @@
 use pretty_assertions_sorted::assert_eq_sorted;
-use surreal_type_generator::{Kind, QueryResult};
+use std::{collections::BTreeMap, sync::Arc};
+
+use surreal_type_generator::{
+    kind,
+    step_2_interpret::interpret_schema,
+    step_3_codegen::{
+        generate_type_info,
+        typescript::{generate_typescript_output, ClientTemplate, GeometryOutput},
+    },
+    Kind, QueryResult,
+};
 
 #[test]
 fn return_and_expressions() -> anyhow::Result<()> {
@@ -60,40 +70,114 @@ DEFINE TABLE placeholder SCHEMAFULL;
     Ok(())
 }
 
-/*
--- arithmetic expressions
+#[test]
+fn arithmetic_expressions() -> anyhow::Result<()> {
+    let query = r#"
 RETURN 1 + 1;
 RETURN 1 - 1;
 RETURN 1 * 1;
 RETURN 1 ** 1;
 RETURN 1 / 1;
 RETURN 1 % 2;
-*/
+"#;
+    let schema = r#"
+DEFINE TABLE placeholder SCHEMAFULL;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(return_types, vec![Kind::Number; 6]);
+
+    Ok(())
+}
 
-/*
--- shortcircuiting expressions
+#[test]
+fn short_circuiting_expressions() -> anyhow::Result<()> {
+    let query = r#"
 RETURN 1 ?: 1;
 RETURN 1 ?: null;
 RETURN null ?? 1;
 RETURN 1 ?? 1;
-*/
-
-// -- ## TODOS ##
-// -- !~
-// -- ~
-// -- ?~
-// -- *~
-// -- CONTAINS
-// -- CONTAINSNOT
-// -- CONTAINSALL
-// -- CONTAINSANY
-// -- CONTAINSNONE
-// -- INSIDE
-// -- NOTINSIDE
-// -- ALLINSIDE
-// -- ANYINSIDE
-// -- NONEINSIDE
-// -- OUTSIDE
-// -- INTERSECTS
-// -- @@ (MATCHES)
-// -- <|4|> #KNN
+RETURN "foo" ?? 1;
+"#;
+    let schema = r#"
+DEFINE TABLE placeholder SCHEMAFULL;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            Kind::Number,
+            Kind::Either(vec![Kind::Number, Kind::Null]),
+            Kind::Number,
+            Kind::Number,
+            Kind::String,
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn coalescing_expressions_unwrap_options() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    nickname ?? name AS display_name,
+    nickname || name AS truthy_name,
+    nickname ?? 0 AS nickname_or_zero,
+    status ?? "active" AS status,
+    (nickname ?? middle_name) ?? name AS any_name
+FROM
+    user
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD nickname ON user TYPE option<string>;
+DEFINE FIELD middle_name ON user TYPE option<string | null>;
+DEFINE FIELD status ON user TYPE option<"active" | "banned">;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            display_name: kind!(String),
+            truthy_name: kind!(String),
+            nickname_or_zero: kind!(Either[kind!(String), kind!(Number)]),
+            // the literal members are subsumed by the `string` default
+            status: kind!(String),
+            any_name: kind!(String)
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn coalescing_typescript_output_has_no_duplicate_members() -> anyhow::Result<()> {
+    let schema = r#"
+DEFINE TABLE stats SCHEMAFULL;
+DEFINE FIELD count ON stats TYPE option<int>;
+DEFINE FIELD ratio ON stats TYPE float;
+"#;
+
+    let state = Arc::new(interpret_schema(schema, BTreeMap::new())?);
+    let types = vec![generate_type_info(
+        "get_stats.surql",
+        "SELECT count ?? ratio AS value FROM stats;",
+        state,
+    )?];
+
+    let output =
+        generate_typescript_output(&types, "", &ClientTemplate::Class, GeometryOutput::GeoJson)?;
+    assert!(output.contains("value: number,"), "{}", output);
+
+    Ok(())
+}
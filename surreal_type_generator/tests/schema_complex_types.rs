@@ -59,3 +59,34 @@ DEFINE FIELD bar ON foo FLEXIBLE TYPE object;
 
     Ok(())
 }
+
+#[test]
+fn schema_array_item_definitions() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    tags,
+    scores
+FROM foo;
+"#;
+
+    let schema = r#"
+DEFINE TABLE foo SCHEMAFULL;
+DEFINE FIELD tags ON foo TYPE array;
+DEFINE FIELD tags.* ON foo TYPE string;
+DEFINE FIELD scores ON foo TYPE option<array<number>>;
+DEFINE FIELD scores.* ON foo TYPE int;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            tags: kind!([kind!(String)]),
+            scores: kind!(Opt(kind!([kind!(Int)])))
+        })])]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, var_map, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD nickname ON user TYPE option<string>;
+DEFINE FIELD age ON user TYPE int;
+DEFINE FIELD tags ON user TYPE array<string>;
+DEFINE FIELD address ON user;
+DEFINE FIELD address.city ON user TYPE string;
+DEFINE FIELD address.location ON user;
+DEFINE FIELD address.location.country ON user TYPE string;
+DEFINE FIELD email ON user TYPE string VALUE string::lowercase($value);
+DEFINE FIELD created_at ON user TYPE datetime READONLY;
+DEFINE FIELD updated_at ON user TYPE datetime VALUE time::now();
+DEFINE FIELD expires ON user TYPE datetime;
+DEFINE FIELD timeout ON user TYPE option<duration>;
+"#;
+
+#[test]
+fn set_infers_parameters_from_fields() -> anyhow::Result<()> {
+    let query = r#"
+UPDATE user SET
+    name = $name,
+    nickname = $nickname,
+    email = $email,
+    address.location.country = $country;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            name: kind!(String),
+            nickname: kind!(Opt(kind!(String))),
+            email: kind!(String),
+            country: kind!(String),
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn set_operators_infer_parameters() -> anyhow::Result<()> {
+    let query = r#"
+UPSERT user SET age += $years, tags += $tags;
+UPDATE user SET age -= $age_difference, tags -= $removed;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            years: kind!(Int),
+            age_difference: kind!(Int),
+            tags: kind!(Either[kind!(String), kind!([kind!(String)])]),
+            removed: kind!(Either[kind!(String), kind!([kind!(String)])]),
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn set_operators_support_strings_and_durations() -> anyhow::Result<()> {
+    let query = r#"
+UPDATE user SET name += $suffix, expires += $extension, timeout -= $reduction;
+UPDATE user SET name += "!", expires -= 1d, timeout += 1h;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            suffix: kind!(String),
+            extension: kind!(Duration),
+            reduction: kind!(Duration),
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn set_infers_nested_objects_and_uses_cast_parameters() -> anyhow::Result<()> {
+    let query = r#"
+<string> $name;
+
+CREATE user SET
+    name = $name,
+    address = $address,
+    created_at = $created_at;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            name: kind!(String),
+            address: kind!({
+                city: kind!(String),
+                location: kind!({
+                    country: kind!(String)
+                })
+            }),
+            created_at: kind!(Datetime),
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn set_rejects_readonly_and_value_fields() -> anyhow::Result<()> {
+    for (query, error) in [
+        (
+            "UPDATE user SET created_at = time::now();",
+            "Field `created_at` is `READONLY` and cannot be updated",
+        ),
+        (
+            "CREATE user SET updated_at = time::now();",
+            "Field `updated_at` has a `VALUE` clause which overrides any value set by the query",
+        ),
+        (
+            "UPDATE user SET name -= $name;",
+            "`-=` cannot be used with `name` of type `string`",
+        ),
+        (
+            "UPDATE user SET age = \"old\";",
+            "Cannot assign `string` to `age` with `=`, which expects `int`",
+        ),
+        (
+            "UPDATE user SET expires += 5;",
+            "Cannot assign `number` to `expires` with `+=`, which expects `duration`",
+        ),
+    ] {
+        match surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA) {
+            Ok(_) => panic!("Expected an error for: {}", query),
+            Err(err) => assert_eq_sorted!(err.to_string(), error),
+        }
+    }
+
+    Ok(())
+}
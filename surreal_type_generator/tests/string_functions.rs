@@ -0,0 +1,107 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, QueryResult};
+
+#[test]
+fn string_function_return_types() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    string::lowercase(name) AS lower,
+    string::concat(name, "-", slug) AS joined,
+    string::split(name, " ") AS parts,
+    string::len(name) AS length,
+    string::is::email(name) AS is_email,
+    string::similarity::fuzzy(name, slug) AS similarity,
+    string::distance::levenshtein(name, slug) AS distance,
+    string::html::sanitize(name) AS sanitized,
+    string::semver::major(version) AS major
+FROM
+    post
+"#;
+    let schema = r#"
+DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD name ON post TYPE string;
+DEFINE FIELD slug ON post TYPE string;
+DEFINE FIELD version ON post TYPE string;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            lower: kind!(String),
+            joined: kind!(String),
+            parts: kind!([kind!(String)]),
+            length: kind!(Int),
+            is_email: kind!(Bool),
+            similarity: kind!(Number),
+            distance: kind!(Number),
+            sanitized: kind!(String),
+            major: kind!(Int)
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn string_function_with_parameter() -> anyhow::Result<()> {
+    let query = r#"
+<string> $title;
+
+RETURN string::lowercase($title);
+"#;
+    let schema = r#""#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(return_types, vec![kind!(String)]);
+
+    Ok(())
+}
+
+#[test]
+fn builtin_function_arguments_infer_parameters() -> anyhow::Result<()> {
+    let query = r#"
+RETURN string::lowercase($value);
+RETURN string::slice($title, $start, 5);
+RETURN array::len($items);
+RETURN math::sum($scores);
+RETURN time::format($at, $format);
+"#;
+    let schema = r#""#;
+
+    let QueryResult {
+        return_types,
+        variables,
+        ..
+    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!(String),
+            kind!(String),
+            kind!(Number),
+            kind!(Number),
+            kind!(String),
+        ]
+    );
+
+    assert_eq_sorted!(
+        variables,
+        surreal_type_generator::var_map! {
+            value: kind!(String),
+            title: kind!(String),
+            start: kind!(Int),
+            items: kind!([kind!(Any)]),
+            scores: kind!([kind!(Number)]),
+            at: kind!(Datetime),
+            format: kind!(String)
+        }
+    );
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use pretty_assertions_sorted::assert_eq_sorted;
-use surreal_type_generator::{step_3_codegen::QueryResult, Kind};
+use surreal_type_generator::{kind, step_3_codegen::QueryResult, Kind};
 
 #[test]
 fn transaction_return_type() -> anyhow::Result<()> {
@@ -22,3 +22,135 @@ DEFINE TABLE foo SCHEMAFULL;
 
     Ok(())
 }
+
+const SCHEMA: &str = r#"
+DEFINE TABLE foo SCHEMAFULL;
+DEFINE TABLE bar SCHEMAFULL;
+"#;
+
+#[test]
+fn transaction_with_return_only_returns_the_returned_value() -> anyhow::Result<()> {
+    let query = r#"
+BEGIN;
+CREATE ONLY foo;
+CREATE ONLY bar;
+RETURN 1;
+COMMIT;
+RETURN "done";
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(return_types, vec![kind!(Number), kind!(String)]);
+
+    Ok(())
+}
+
+#[test]
+fn transaction_without_return_keeps_every_result() -> anyhow::Result<()> {
+    let query = r#"
+BEGIN;
+CREATE ONLY foo;
+CREATE ONLY bar;
+COMMIT;
+RETURN "done";
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            kind!({ id: kind!(Record["foo"]) }),
+            kind!({ id: kind!(Record["bar"]) }),
+            kind!(String),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn transaction_with_nested_and_final_return() -> anyhow::Result<()> {
+    let query = r#"
+<bool> $condition;
+
+BEGIN;
+IF $condition {
+    RETURN "early";
+};
+RETURN 2;
+COMMIT;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![Kind::Either(vec![kind!(String), kind!(Number)])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn transaction_with_nested_return_unions_the_results() -> anyhow::Result<()> {
+    let query = r#"
+<bool> $condition;
+
+BEGIN;
+IF $condition {
+    RETURN "early";
+};
+COMMIT;
+RETURN 2;
+
+BEGIN;
+CREATE ONLY foo;
+IF $condition {
+    RETURN "early";
+};
+CREATE ONLY bar;
+COMMIT;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![
+            Kind::Either(vec![kind!(Null), kind!(String)]),
+            kind!(Number),
+            Kind::Either(vec![kind!({ id: kind!(Record["foo"]) }), kind!(String)]),
+            kind!(Opt(kind!(Null))),
+            kind!(Opt(kind!({ id: kind!(Record["bar"]) }))),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn transaction_narrows_after_nested_return() -> anyhow::Result<()> {
+    let query = r#"
+<option<string>> $name;
+
+BEGIN;
+IF $name = NONE {
+    RETURN "missing";
+};
+RETURN $name;
+COMMIT;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(return_types, vec![kind!(String)]);
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, var_map, QueryResult};
+
+#[test]
+fn type_thing_with_table_literal() -> anyhow::Result<()> {
+    let query = r#"
+SELECT * FROM ONLY type::thing("user", $id);
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD id ON user TYPE int;
+DEFINE FIELD name ON user TYPE string;
+"#;
+
+    let QueryResult {
+        return_types,
+        variables,
+        ..
+    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(variables, var_map! { id: kind!(Int) });
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!({
+            id: kind!(Record["user"]),
+            name: kind!(String)
+        })]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn type_record_and_casting_functions() -> anyhow::Result<()> {
+    let query = r#"
+<string> $table;
+
+RETURN {
+    user: type::record($user, "user"),
+    any_record: type::thing($table, 1),
+    number: type::int("5"),
+    date: type::datetime("2024-01-01"),
+    text: type::string(5),
+    is_string: type::is::string(5)
+};
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+"#;
+
+    let QueryResult {
+        return_types,
+        variables,
+        ..
+    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            table: kind!(String),
+            user: kind!(Record["user"])
+        }
+    );
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!({
+            user: kind!(Record["user"]),
+            any_record: surreal_type_generator::Kind::Record(vec![]),
+            number: kind!(Int),
+            date: kind!(Datetime),
+            text: kind!(String),
+            is_string: kind!(Bool)
+        })]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn type_field_resolves_literal_fields() -> anyhow::Result<()> {
+    let query = r#"
+SELECT type::field("name") AS name, type::fields(["name", "age"]) AS values FROM user;
+"#;
+    let schema = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD age ON user TYPE int;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            name: kind!(String),
+            values: kind!([kind!(Either[kind!(String), kind!(Int)])])
+        })])]
+    );
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use pretty_assertions_sorted::assert_eq_sorted;
-use surreal_type_generator::{kind, QueryResult};
+use surreal_type_generator::{kind, var_map, Kind, Literal, QueryResult};
 
 #[test]
 fn update_statement_with_set_field() -> anyhow::Result<()> {
@@ -145,3 +145,123 @@ DEFINE FIELD baz ON user TYPE string;
 
     Ok(())
 }
+
+const DATA_SCHEMA: &str = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD name ON user TYPE string;
+DEFINE FIELD age ON user TYPE option<int>;
+DEFINE FIELD address ON user;
+DEFINE FIELD address.city ON user TYPE string;
+DEFINE FIELD address.verified_at ON user TYPE datetime READONLY;
+DEFINE FIELD created_at ON user TYPE datetime DEFAULT time::now() READONLY;
+DEFINE FIELD updated_at ON user TYPE datetime VALUE time::now();
+DEFINE FIELD tags ON user TYPE array<string>;
+DEFINE FIELD tags.* ON user TYPE string;
+"#;
+
+#[test]
+fn update_content_and_replace_infer_the_whole_record() -> anyhow::Result<()> {
+    let query = r#"
+UPDATE user CONTENT $content;
+UPDATE user REPLACE $replacement;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, DATA_SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            // `READONLY` fields keep their stored values, so they are left out
+            content: kind!({
+                name: kind!(String),
+                age: kind!(Opt(kind!(Int))),
+                address: kind!({
+                    city: kind!(String)
+                }),
+                tags: kind!([kind!(String)])
+            }),
+            // `READONLY` fields are replaced too, so they must be provided
+            replacement: kind!({
+                name: kind!(String),
+                age: kind!(Opt(kind!(Int))),
+                address: kind!({
+                    city: kind!(String),
+                    verified_at: kind!(Datetime)
+                }),
+                created_at: kind!(Opt(kind!(Datetime))),
+                id: kind!(Opt(kind!(Record ["user"]))),
+                tags: kind!([kind!(String)])
+            }),
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn update_merge_infers_a_deep_partial() -> anyhow::Result<()> {
+    let query = r#"
+UPDATE user MERGE $changes;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, DATA_SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            changes: kind!({
+                name: kind!(Opt(kind!(String))),
+                age: kind!(Opt(kind!(Int))),
+                address: kind!(Opt(kind!({
+                    city: kind!(Opt(kind!(String)))
+                }))),
+                tags: kind!(Opt(kind!([kind!(String)])))
+            }),
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn update_patch_infers_json_patch_operations() -> anyhow::Result<()> {
+    let query = r#"
+UPDATE user PATCH $patch;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, DATA_SCHEMA)?;
+
+    let op = |op: &str| Kind::Literal(Literal::String(op.into()));
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            patch: kind!([Kind::Either(vec![
+                kind!({
+                    op: Kind::Either(vec![op("add"), op("replace"), op("test")]),
+                    path: kind!(String),
+                    value: kind!(Any)
+                }),
+                kind!({
+                    op: op("remove"),
+                    path: kind!(String)
+                }),
+                kind!({
+                    op: Kind::Either(vec![op("copy"), op("move")]),
+                    from: kind!(String),
+                    path: kind!(String)
+                }),
+                kind!({
+                    op: op("change"),
+                    path: kind!(String),
+                    value: kind!(String)
+                }),
+            ])]),
+        }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, var_map, QueryResult};
+
+#[test]
+fn vector_functions() -> anyhow::Result<()> {
+    let query = r#"
+<array<float>> $vector;
+
+SELECT
+    id,
+    vector::distance::knn() AS distance,
+    vector::similarity::cosine(embedding, $vector) AS similarity,
+    vector::add(embedding, $vector) AS sum
+FROM
+    document
+WHERE
+    embedding <|3|> $vector
+"#;
+    let schema = r#"
+DEFINE TABLE document SCHEMAFULL;
+DEFINE FIELD embedding ON document TYPE array<float>;
+DEFINE INDEX embedding_index ON document FIELDS embedding MTREE DIMENSION 3;
+"#;
+
+    let QueryResult { return_types, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            id: kind!(Record["document"]),
+            distance: kind!(Number),
+            similarity: kind!(Number),
+            sum: kind!([kind!(Number)])
+        })])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn knn_operators_infer_vector_parameter() -> anyhow::Result<()> {
+    let query = r#"
+SELECT
+    embedding <|3|> $vector AS is_neighbour,
+    embedding <|3,40|> $approximate AS is_approximate_neighbour
+FROM
+    document
+"#;
+    let schema = r#"
+DEFINE TABLE document SCHEMAFULL;
+DEFINE FIELD embedding ON document TYPE array<float>;
+"#;
+
+    let QueryResult {
+        return_types,
+        variables,
+        ..
+    } = surreal_type_generator::step_3_codegen::query_to_return_type(query, schema)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            vector: kind!([kind!(Number)]),
+            approximate: kind!([kind!(Number)])
+        }
+    );
+
+    assert_eq_sorted!(
+        return_types,
+        vec![kind!([kind!({
+            is_neighbour: kind!(Bool),
+            is_approximate_neighbour: kind!(Bool)
+        })])]
+    );
+
+    Ok(())
+}
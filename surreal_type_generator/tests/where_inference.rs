@@ -0,0 +1,90 @@
+use pretty_assertions_sorted::assert_eq_sorted;
+use surreal_type_generator::{kind, var_map, Kind, Literal, QueryResult};
+
+const SCHEMA: &str = r#"
+DEFINE TABLE user SCHEMAFULL;
+DEFINE FIELD email ON user TYPE string;
+DEFINE FIELD nickname ON user TYPE option<string>;
+DEFINE FIELD age ON user TYPE int;
+DEFINE FIELD status ON user TYPE "active" | "banned";
+DEFINE FIELD tags ON user TYPE array<string>;
+DEFINE FIELD address ON user;
+DEFINE FIELD address.city ON user TYPE string;
+"#;
+
+#[test]
+fn where_infers_compared_parameters() -> anyhow::Result<()> {
+    let query = r#"
+SELECT * FROM user WHERE
+    email = $email
+    AND nickname != $nickname
+    AND age >= $min_age
+    AND address.city = $city
+    AND (status IN $statuses OR $tag IN tags)
+    AND !(tags CONTAINSANY $excluded_tags);
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            email: kind!(String),
+            nickname: kind!(Opt(kind!(String))),
+            min_age: kind!(Int),
+            city: kind!(String),
+            statuses: kind!([Kind::Either(vec![
+                Kind::Literal(Literal::String("active".into())),
+                Kind::Literal(Literal::String("banned".into())),
+            ])]),
+            tag: kind!(String),
+            excluded_tags: kind!([kind!(String)]),
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn where_inference_applies_to_update_and_delete() -> anyhow::Result<()> {
+    let query = r#"
+UPDATE user SET age = $age WHERE email = $email AND age < $age;
+DELETE user WHERE tags CONTAINS $tag;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            age: kind!(Int),
+            email: kind!(String),
+            tag: kind!(String),
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn where_inference_does_not_override_casts() -> anyhow::Result<()> {
+    let query = r#"
+<array<string>> $email;
+
+SELECT * FROM user WHERE email IN $email;
+"#;
+
+    let QueryResult { variables, .. } =
+        surreal_type_generator::step_3_codegen::query_to_return_type(query, SCHEMA)?;
+
+    assert_eq_sorted!(
+        variables,
+        var_map! {
+            email: kind!([kind!(String)]),
+        }
+    );
+
+    Ok(())
+}